@@ -0,0 +1,355 @@
+//! Fingerprinted asset URLs and Subresource Integrity
+
+use crate::simple_element::write_attribute;
+use crate::Render;
+use base64::Engine;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha384};
+use std::collections::BTreeMap;
+use std::fmt::{self, Formatter, Write};
+use std::path::Path;
+use std::{fs, io};
+
+/// Maps logical asset names, like `app.js`, to their fingerprinted URLs and integrity hashes.
+///
+/// A manifest is either loaded from a JSON file written by the asset pipeline, or built by
+/// hashing a directory of built assets, for example from a build script. Its JSON maps every
+/// name to a URL, or to an object with a `url` and an optional `integrity`:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// use render::assets::{AssetManifest, Script, Stylesheet};
+///
+/// let manifest = AssetManifest::from_json(r#"{
+///     "app.js": { "url": "/assets/app.3f2a.js", "integrity": "sha384-abc" },
+///     "app.css": "/assets/app.91cc.css"
+/// }"#).unwrap();
+///
+/// let rendered = html! {
+///     <head>
+///         <Stylesheet name={"app.css"} manifest={&manifest} />
+///         <Script name={"app.js"} manifest={&manifest} />
+///     </head>
+/// };
+///
+/// assert_eq!(
+///     rendered,
+///     concat!(
+///         r#"<head><link rel="stylesheet" href="/assets/app.91cc.css"/>"#,
+///         r#"<script src="/assets/app.3f2a.js" integrity="sha384-abc" crossorigin="anonymous">"#,
+///         "</script></head>"
+///     )
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetManifest {
+    assets: BTreeMap<String, Asset>,
+}
+
+/// A built asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Asset {
+    pub url: String,
+    /// The Subresource Integrity hash, like `sha384-...`
+    pub integrity: Option<String>,
+}
+
+impl Asset {
+    /// An asset served from `url`, fingerprinted and hashed using its contents
+    pub fn from_contents(url: &str, contents: &[u8]) -> Self {
+        let digest = Sha384::digest(contents);
+        let fingerprint: String = digest[..8]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let separator = if url.contains('?') { '&' } else { '?' };
+
+        Self {
+            url: format!("{}{}v={}", url, separator, fingerprint),
+            integrity: Some(format!(
+                "sha384-{}",
+                base64::engine::general_purpose::STANDARD.encode(digest)
+            )),
+        }
+    }
+}
+
+impl AssetManifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, asset: Asset) {
+        self.assets.insert(name.into(), asset);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Asset> {
+        self.assets.get(name)
+    }
+
+    /// Parses a JSON manifest
+    pub fn from_json(json: &str) -> Result<Self, AssetError> {
+        let entries: Map<String, Value> = serde_json::from_str(json).map_err(AssetError::Json)?;
+        let mut manifest = Self::new();
+
+        for (name, entry) in entries {
+            let asset = match entry {
+                Value::String(url) => Asset {
+                    url,
+                    integrity: None,
+                },
+                Value::Object(mut fields) => {
+                    match (fields.remove("url"), fields.remove("integrity")) {
+                        (Some(Value::String(url)), None)
+                        | (Some(Value::String(url)), Some(Value::Null)) => Asset {
+                            url,
+                            integrity: None,
+                        },
+                        (Some(Value::String(url)), Some(Value::String(integrity))) => Asset {
+                            url,
+                            integrity: Some(integrity),
+                        },
+                        _ => return Err(AssetError::InvalidEntry(name)),
+                    }
+                }
+                _ => return Err(AssetError::InvalidEntry(name)),
+            };
+            manifest.insert(name, asset);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Loads a JSON manifest file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        let json = fs::read_to_string(path).map_err(AssetError::Io)?;
+        Self::from_json(&json)
+    }
+
+    /// Hashes every file under `dir`. A file at `dir/js/app.js` is named `js/app.js`, and is
+    /// served from `{url_prefix}/js/app.js?v={fingerprint}`.
+    pub fn hash_dir(dir: impl AsRef<Path>, url_prefix: &str) -> Result<Self, AssetError> {
+        let mut manifest = Self::new();
+        manifest.hash_files(dir.as_ref(), "", url_prefix.trim_end_matches('/'))?;
+        Ok(manifest)
+    }
+
+    fn hash_files(
+        &mut self,
+        dir: &Path,
+        name_prefix: &str,
+        url_prefix: &str,
+    ) -> Result<(), AssetError> {
+        for entry in fs::read_dir(dir).map_err(AssetError::Io)? {
+            let entry = entry.map_err(AssetError::Io)?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_str().ok_or_else(|| {
+                AssetError::InvalidEntry(entry.path().to_string_lossy().into_owned())
+            })?;
+            let name = format!("{}{}", name_prefix, file_name);
+
+            if entry.file_type().map_err(AssetError::Io)?.is_dir() {
+                self.hash_files(&entry.path(), &format!("{}/", name), url_prefix)?;
+            } else {
+                let contents = fs::read(entry.path()).map_err(AssetError::Io)?;
+                let url = format!("{}/{}", url_prefix, name);
+                self.insert(name, Asset::from_contents(&url, &contents));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the manifest, so it can be written at build time and loaded at runtime
+    pub fn to_json(&self) -> String {
+        let entries: Map<String, Value> = self
+            .assets
+            .iter()
+            .map(|(name, asset)| {
+                let mut fields = Map::new();
+                fields.insert("url".into(), asset.url.clone().into());
+                if let Some(integrity) = &asset.integrity {
+                    fields.insert("integrity".into(), integrity.clone().into());
+                }
+                (name.clone(), Value::Object(fields))
+            })
+            .collect();
+
+        Value::Object(entries).to_string()
+    }
+
+    fn resolve(&self, name: &str) -> Result<&Asset, fmt::Error> {
+        self.get(name).ok_or(fmt::Error)
+    }
+}
+
+/// An error loading or building an [`AssetManifest`](struct.AssetManifest.html)
+#[derive(Debug)]
+pub enum AssetError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// An entry with a name or value that can't be used
+    InvalidEntry(String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed reading assets: {}", error),
+            Self::Json(error) => write!(f, "invalid asset manifest: {}", error),
+            Self::InvalidEntry(name) => write!(f, "invalid asset manifest entry `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Json(error) => Some(error),
+            Self::InvalidEntry(_) => None,
+        }
+    }
+}
+
+fn write_integrity<W: Write>(asset: &Asset, writer: &mut W) -> fmt::Result {
+    if let Some(integrity) = &asset.integrity {
+        write_attribute("integrity", integrity.as_str().into(), writer)?;
+        write_attribute("crossorigin", "anonymous".into(), writer)?;
+    }
+    Ok(())
+}
+
+/// Adds the nonce when rendered by a [`Csp`](../csp/struct.Csp.html), like script and style
+/// elements
+#[cfg(feature = "csp")]
+fn write_nonce<W: Write>(writer: &mut W) -> fmt::Result {
+    match crate::csp::active_nonce() {
+        Some(nonce) => write_attribute("nonce", nonce.as_str().into(), writer),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(feature = "csp"))]
+fn write_nonce<W: Write>(_writer: &mut W) -> fmt::Result {
+    Ok(())
+}
+
+/// A `<script>` element loading an asset from the manifest.
+///
+/// Rendering fails if the manifest has no asset with that name.
+#[derive(Debug)]
+pub struct Script<'a> {
+    pub name: &'a str,
+    pub manifest: &'a AssetManifest,
+}
+
+impl Render for Script<'_> {
+    fn render_into<W: Write>(self, writer: &mut W) -> fmt::Result {
+        let asset = self.manifest.resolve(self.name)?;
+        writer.write_str("<script")?;
+        write_attribute("src", asset.url.as_str().into(), writer)?;
+        write_integrity(asset, writer)?;
+        write_nonce(writer)?;
+        writer.write_str("></script>")
+    }
+}
+
+/// A stylesheet `<link>` element loading an asset from the manifest.
+///
+/// Rendering fails if the manifest has no asset with that name.
+#[derive(Debug)]
+pub struct Stylesheet<'a> {
+    pub name: &'a str,
+    pub manifest: &'a AssetManifest,
+}
+
+impl Render for Stylesheet<'_> {
+    fn render_into<W: Write>(self, writer: &mut W) -> fmt::Result {
+        let asset = self.manifest.resolve(self.name)?;
+        writer.write_str("<link")?;
+        write_attribute("rel", "stylesheet".into(), writer)?;
+        write_attribute("href", asset.url.as_str().into(), writer)?;
+        write_integrity(asset, writer)?;
+        write_nonce(writer)?;
+        writer.write_str("/>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn hashes_directories() {
+        let dir = std::env::temp_dir().join(format!("render-assets-{}", std::process::id()));
+        fs::create_dir_all(dir.join("js")).unwrap();
+        fs::write(dir.join("js/app.js"), "alert(1)").unwrap();
+
+        let manifest = AssetManifest::hash_dir(&dir, "/static/");
+        fs::remove_dir_all(&dir).unwrap();
+        let manifest = manifest.unwrap();
+
+        let integrity = "sha384-HT2E9NfWiuQ/w1PRai+hTyqW16NIoCGA/m8VQDUopfAtcz6YQjtsMmQd5uRbVDpW";
+        let asset = Asset {
+            url: "/static/js/app.js?v=1d3d84f4d7d68ae4".into(),
+            integrity: Some(integrity.into()),
+        };
+        assert_eq!(manifest.get("js/app.js"), Some(&asset));
+        assert_eq!(
+            AssetManifest::from_json(&manifest.to_json()).unwrap(),
+            manifest
+        );
+    }
+
+    #[test]
+    fn fails_rendering_missing_assets() {
+        let manifest = AssetManifest::new();
+        let mut buf = String::new();
+        let script = Script {
+            name: "missing.js",
+            manifest: &manifest,
+        };
+        assert!(script.render_into(&mut buf).is_err());
+    }
+
+    #[cfg(feature = "csp")]
+    #[test]
+    fn stamps_the_csp_nonce() {
+        let manifest =
+            AssetManifest::from_json(r#"{"app.js": "/app.1.js", "app.css": "/app.2.css"}"#)
+                .unwrap();
+        let mut csp = crate::csp::Csp::new().nonce("abc").unwrap();
+        let rendered = csp
+            .render((
+                Script {
+                    name: "app.js",
+                    manifest: &manifest,
+                },
+                Stylesheet {
+                    name: "app.css",
+                    manifest: &manifest,
+                },
+            ))
+            .unwrap();
+
+        assert_eq!(
+            rendered,
+            concat!(
+                r#"<script src="/app.1.js" nonce="abc"></script>"#,
+                r#"<link rel="stylesheet" href="/app.2.css" nonce="abc"/>"#
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        let error = AssetManifest::from_json(r#"{ "app.js": { "integrity": "sha384-abc" } }"#);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "invalid asset manifest entry `app.js`"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Values of simple element attributes
+
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+/// The value of a [`SimpleElement`](struct.SimpleElement.html) attribute.
+///
+/// Anything that converts into a `Cow<str>` converts into a text value. Event handler
+/// attributes, like `onclick`, only render values that were explicitly marked as trusted
+/// scripts using [`TrustedScript`](struct.TrustedScript.html). URL attributes, like `href`,
+/// only render URLs with a safe scheme, unless they were marked using
+/// [`TrustedUrl`](struct.TrustedUrl.html).
+pub enum AttributeValue<'a> {
+    Text(Cow<'a, str>),
+    TrustedScript(Cow<'a, str>),
+    TrustedUrl(Cow<'a, str>),
+    /// Text that is written straight into the output when the attribute is rendered.
+    /// This is what `rsx!` uses for interpolated strings, like `class="btn btn-{kind}"`.
+    Interpolated(&'a dyn Fn(&mut dyn Write) -> fmt::Result),
+}
+
+impl<'a> AttributeValue<'a> {
+    /// The value of an event handler attribute, which must be a trusted script.
+    /// This is what `rsx!` uses for attributes that start with `on`.
+    pub fn event_handler(script: TrustedScript<'a>) -> Self {
+        Self::TrustedScript(script.0)
+    }
+
+    /// A value written by `write` when the attribute is rendered
+    pub fn interpolated(write: &'a dyn Fn(&mut dyn Write) -> fmt::Result) -> Self {
+        Self::Interpolated(write)
+    }
+
+    /// The value, unless it is interpolated
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Text(value) | Self::TrustedScript(value) | Self::TrustedUrl(value) => Some(value),
+            Self::Interpolated(_) => None,
+        }
+    }
+}
+
+impl fmt::Debug for AttributeValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(value) => f.debug_tuple("Text").field(value).finish(),
+            Self::TrustedScript(value) => f.debug_tuple("TrustedScript").field(value).finish(),
+            Self::TrustedUrl(value) => f.debug_tuple("TrustedUrl").field(value).finish(),
+            Self::Interpolated(_) => f.debug_tuple("Interpolated").finish(),
+        }
+    }
+}
+
+impl<'a, T: Into<Cow<'a, str>>> From<T> for AttributeValue<'a> {
+    fn from(value: T) -> Self {
+        Self::Text(value.into())
+    }
+}
+
+/// A script that is trusted to be rendered in an event handler attribute, like `onclick`.
+///
+/// Event handlers run their value as JavaScript, so escaping can't make untrusted input safe
+/// there. `rsx!` refuses to compile event handler attributes with any other value:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// use render::TrustedScript;
+///
+/// let rendered = html! { <button onclick={TrustedScript::new("history.back()")} /> };
+/// assert_eq!(rendered, r#"<button onclick="history.back()"/>"#);
+/// ```
+///
+/// ```compile_fail
+/// # use render::html;
+/// let rendered = html! { <button onclick={"history.back()"} /> };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedScript<'a>(Cow<'a, str>);
+
+impl<'a> TrustedScript<'a> {
+    pub fn new(script: impl Into<Cow<'a, str>>) -> Self {
+        Self(script.into())
+    }
+}
+
+impl<'a> From<TrustedScript<'a>> for AttributeValue<'a> {
+    fn from(script: TrustedScript<'a>) -> Self {
+        Self::event_handler(script)
+    }
+}
+
+/// A URL that is trusted to be rendered in a URL attribute, like `href` or `src`, whatever its
+/// scheme is.
+///
+/// URLs with a scheme other than the
+/// [`SAFE_URL_SCHEMES`](html_escaping/constant.SAFE_URL_SCHEMES.html) are replaced with
+/// `about:invalid`, so a `javascript:` URL can't be injected:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// use render::TrustedUrl;
+///
+/// let user_url = "javascript:alert(1)";
+/// assert_eq!(html! { <a href={user_url} /> }, r#"<a href="about:invalid"/>"#);
+///
+/// let pixel = TrustedUrl::new("data:image/gif;base64,R0lGODlhAQABAAAAACw=");
+/// assert_eq!(
+///     html! { <img src={pixel} /> },
+///     r#"<img src="data:image/gif;base64,R0lGODlhAQABAAAAACw="/>"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedUrl<'a>(Cow<'a, str>);
+
+impl<'a> TrustedUrl<'a> {
+    pub fn new(url: impl Into<Cow<'a, str>>) -> Self {
+        Self(url.into())
+    }
+}
+
+impl<'a> From<TrustedUrl<'a>> for AttributeValue<'a> {
+    fn from(url: TrustedUrl<'a>) -> Self {
+        Self::TrustedUrl(url.0)
+    }
+}
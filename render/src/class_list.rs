@@ -0,0 +1,237 @@
+//! Conditional `class` attribute values
+
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter, Result};
+
+/// A list of CSS class names, deduplicated and joined by spaces when rendered.
+///
+/// Usually built using the [`classes!`](macro.classes.html) macro, and can be
+/// passed directly as an attribute value:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::{classes, html};
+/// let is_active = true;
+/// let size: Option<&str> = None;
+///
+/// let rendered = html! {
+///     <button class={classes!("btn", ("btn-active", is_active), size, "btn")} />
+/// };
+///
+/// assert_eq!(rendered, r#"<button class="btn btn-active"/>"#);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClassList<'a> {
+    names: Vec<Cow<'a, str>>,
+}
+
+impl<'a> ClassList<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds class names to the list, skipping the ones that are already in it
+    pub fn push<C: ClassNames<'a>>(&mut self, classes: C) {
+        classes.add_to(self);
+    }
+
+    /// Adds class names to the list and returns it, for chaining
+    pub fn with<C: ClassNames<'a>>(mut self, classes: C) -> Self {
+        self.push(classes);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|name| name.as_ref())
+    }
+
+    fn add_name(&mut self, name: Cow<'a, str>) {
+        if !name.is_empty() && !self.names.contains(&name) {
+            self.names.push(name);
+        }
+    }
+
+    fn add_borrowed(&mut self, names: &'a str) {
+        for name in names.split_whitespace() {
+            self.add_name(Cow::Borrowed(name));
+        }
+    }
+
+    fn add_owned(&mut self, names: String) {
+        if names.contains(char::is_whitespace) {
+            for name in names.split_whitespace() {
+                self.add_name(Cow::Owned(name.to_owned()));
+            }
+        } else {
+            self.add_name(Cow::Owned(names));
+        }
+    }
+}
+
+impl Display for ClassList<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (index, name) in self.names.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<ClassList<'a>> for Cow<'a, str> {
+    fn from(mut list: ClassList<'a>) -> Self {
+        if list.names.len() == 1 {
+            list.names.remove(0)
+        } else {
+            Cow::Owned(list.to_string())
+        }
+    }
+}
+
+impl<'a, C: ClassNames<'a>> std::iter::FromIterator<C> for ClassList<'a> {
+    fn from_iter<I: IntoIterator<Item = C>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<'a, C: ClassNames<'a>> Extend<C> for ClassList<'a> {
+    fn extend<I: IntoIterator<Item = C>>(&mut self, iter: I) {
+        for classes in iter {
+            self.push(classes);
+        }
+    }
+}
+
+/// Values that can be added to a [`ClassList`](struct.ClassList.html).
+///
+/// Strings may contain multiple whitespace-separated class names.
+/// `(classes, bool)` pairs are added only when the flag is `true`, and `Option`s only when
+/// they are `Some`. Arbitrary iterators can be collected into a `ClassList` first.
+pub trait ClassNames<'a> {
+    fn add_to(self, list: &mut ClassList<'a>);
+}
+
+impl<'a> ClassNames<'a> for &'a str {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        list.add_borrowed(self);
+    }
+}
+
+impl<'a> ClassNames<'a> for &'a String {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        list.add_borrowed(self);
+    }
+}
+
+impl<'a> ClassNames<'a> for String {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        list.add_owned(self);
+    }
+}
+
+impl<'a> ClassNames<'a> for Cow<'a, str> {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        match self {
+            Cow::Borrowed(names) => list.add_borrowed(names),
+            Cow::Owned(names) => list.add_owned(names),
+        }
+    }
+}
+
+impl<'a> ClassNames<'a> for ClassList<'a> {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        for name in self.names {
+            list.add_name(name);
+        }
+    }
+}
+
+impl<'a, C: ClassNames<'a>> ClassNames<'a> for (C, bool) {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        if self.1 {
+            self.0.add_to(list);
+        }
+    }
+}
+
+impl<'a, C: ClassNames<'a>> ClassNames<'a> for Option<C> {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        if let Some(classes) = self {
+            classes.add_to(list);
+        }
+    }
+}
+
+impl<'a, C: ClassNames<'a>> ClassNames<'a> for Vec<C> {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        list.extend(self);
+    }
+}
+
+impl<'a, C: ClassNames<'a>, const N: usize> ClassNames<'a> for [C; N] {
+    fn add_to(self, list: &mut ClassList<'a>) {
+        list.extend(IntoIterator::into_iter(self));
+    }
+}
+
+/// Builds a [`ClassList`](struct.ClassList.html) out of string literals, `(name, bool)` pairs,
+/// `Option`s and collections of class names
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::classes;
+/// let variant = String::from("primary");
+/// let extra = vec!["rounded", "shadow btn"];
+///
+/// let list = classes!("btn", ("disabled", false), Some(variant), extra);
+/// assert_eq!(list.to_string(), "btn primary rounded shadow");
+/// ```
+#[macro_export]
+macro_rules! classes {
+    ($($classes:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut list = $crate::ClassList::new();
+        $(list.push($classes);)*
+        list
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn dedupes_and_splits_names() {
+        let list = ClassList::new()
+            .with("a b")
+            .with(String::from("b  c"))
+            .with(("a", true));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn collects_from_iterators() {
+        let list: ClassList = (1..=3).map(|n| format!("col-{}", n)).collect();
+        assert_eq!(list.to_string(), "col-1 col-2 col-3");
+    }
+
+    #[test]
+    fn borrows_a_single_name() {
+        let value = Cow::from(ClassList::new().with("single"));
+        assert!(matches!(value, Cow::Borrowed("single")));
+        assert_eq!(Cow::from(ClassList::new()), "");
+    }
+}
@@ -0,0 +1,286 @@
+//! Content-Security-Policy nonces and inline hashes
+
+use crate::html_escaping::RawText;
+use crate::simple_element::Attributes;
+use crate::{spread_attributes, Render};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::fmt::{Result, Write};
+
+/// Renders components under a strict Content-Security-Policy.
+///
+/// Every `<script>` and `<style>` [`SimpleElement`](../struct.SimpleElement.html) rendered by
+/// [`render`](#method.render) gets the configured nonce. With
+/// [`hash_inline`](#method.hash_inline), the SHA-256 hash of every inline block is collected
+/// as well. Afterwards, [`header_value`](#method.header_value) returns the matching
+/// `Content-Security-Policy` header value.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::rsx;
+/// use render::csp::Csp;
+///
+/// let mut csp = Csp::new().nonce("r4nd0m").unwrap().hash_inline();
+/// let page = csp
+///     .render(rsx! {
+///         <head>
+///             <style>{"body { color: red }"}</style>
+///         </head>
+///     })
+///     .unwrap();
+///
+/// assert_eq!(
+///     page,
+///     r#"<head><style nonce="r4nd0m">body { color: red }</style></head>"#
+/// );
+/// assert_eq!(
+///     csp.header_value(),
+///     concat!(
+///         "script-src 'nonce-r4nd0m'; ",
+///         "style-src 'nonce-r4nd0m' 'sha256-kl6HQb5peP+QG0x7FWklMRxR/HYq4xozK9Oa6BWSDQA='"
+///     )
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Csp {
+    nonce: Option<String>,
+    hash_inline: bool,
+    script_hashes: Vec<String>,
+    style_hashes: Vec<String>,
+}
+
+impl Csp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stamps `nonce` onto every script and style element. It must be unique per response.
+    ///
+    /// The nonce goes into the header as is, so it can only contain base64 or base64url
+    /// characters.
+    pub fn nonce(mut self, nonce: impl Into<String>) -> std::result::Result<Self, InvalidNonce> {
+        let nonce = nonce.into();
+        if !is_valid_nonce(&nonce) {
+            return Err(InvalidNonce { nonce });
+        }
+
+        self.nonce = Some(nonce);
+        Ok(self)
+    }
+
+    /// Collects the hashes of inline scripts and styles. Scripts with a `src` aren't hashed.
+    pub fn hash_inline(mut self) -> Self {
+        self.hash_inline = true;
+        self
+    }
+
+    /// Renders a component to a string, following the policy
+    pub fn render<R: Render>(
+        &mut self,
+        renderable: R,
+    ) -> std::result::Result<String, std::fmt::Error> {
+        let mut buf = String::new();
+        self.render_into(renderable, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Renders a component into a writer, following the policy
+    pub fn render_into<R: Render, W: Write>(&mut self, renderable: R, writer: &mut W) -> Result {
+        let _restore = RestoreCsp(ACTIVE.with(|active| active.replace(Some(std::mem::take(self)))));
+        let result = renderable.render_into(writer);
+        *self = ACTIVE
+            .with(|active| active.borrow_mut().take())
+            .unwrap_or_default();
+        result
+    }
+
+    /// The hashes of the inline scripts rendered so far, like `'sha256-...'`
+    pub fn script_hashes(&self) -> &[String] {
+        &self.script_hashes
+    }
+
+    /// The hashes of the inline styles rendered so far, like `'sha256-...'`
+    pub fn style_hashes(&self) -> &[String] {
+        &self.style_hashes
+    }
+
+    /// The `Content-Security-Policy` header value allowing the nonce and the collected hashes
+    pub fn header_value(&self) -> String {
+        let directives = [
+            ("script-src", &self.script_hashes),
+            ("style-src", &self.style_hashes),
+        ];
+
+        let mut header = String::new();
+        for (directive, hashes) in directives.iter() {
+            if self.nonce.is_none() && hashes.is_empty() {
+                continue;
+            }
+
+            if !header.is_empty() {
+                header.push_str("; ");
+            }
+            header.push_str(directive);
+            if let Some(nonce) = &self.nonce {
+                header.push_str(" 'nonce-");
+                header.push_str(nonce);
+                header.push('\'');
+            }
+            for hash in hashes.iter() {
+                header.push(' ');
+                header.push_str(hash);
+            }
+        }
+        header
+    }
+}
+
+/// The error returned when a nonce has characters that could change the header's meaning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidNonce {
+    pub nonce: String,
+}
+
+impl std::fmt::Display for InvalidNonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        write!(f, "invalid CSP nonce `{}`, expected base64", self.nonce)
+    }
+}
+
+impl std::error::Error for InvalidNonce {}
+
+fn is_valid_nonce(nonce: &str) -> bool {
+    let value = nonce.trim_end_matches('=');
+    !value.is_empty()
+        && nonce.len() - value.len() <= 2
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '-' | '_'))
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<Csp>> = const { RefCell::new(None) };
+}
+
+struct RestoreCsp(Option<Csp>);
+
+impl Drop for RestoreCsp {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| *active.borrow_mut() = self.0.take());
+    }
+}
+
+/// Adds the nonce to a script or style element's attributes, and returns whether its contents
+/// should be hashed
+pub(crate) fn stamp(attributes: &mut Attributes<'_>) -> bool {
+    ACTIVE.with(|active| match &*active.borrow() {
+        None => false,
+        Some(csp) => {
+            let has_src = attributes.as_ref().is_some_and(|attributes| {
+                attributes.keys().any(|key| key.eq_ignore_ascii_case("src"))
+            });
+            if let Some(nonce) = &csp.nonce {
+                let attributes = attributes.get_or_insert_with(Default::default);
+                spread_attributes(attributes, Some(("nonce", nonce.clone())));
+            }
+            csp.hash_inline && !has_src
+        }
+    })
+}
+
+/// The nonce of the policy being rendered, for elements written without a `SimpleElement`
+#[cfg(feature = "assets")]
+pub(crate) fn active_nonce() -> Option<String> {
+    ACTIVE.with(|active| active.borrow().as_ref().and_then(|csp| csp.nonce.clone()))
+}
+
+/// Records the hash of an inline script or style
+pub(crate) fn record_hash(raw_text: RawText, contents: &str) {
+    let digest = Sha256::digest(contents.as_bytes());
+    let hash = format!(
+        "'sha256-{}'",
+        base64::engine::general_purpose::STANDARD.encode(digest)
+    );
+
+    ACTIVE.with(|active| {
+        if let Some(csp) = &mut *active.borrow_mut() {
+            let hashes = match raw_text {
+                RawText::Script => &mut csp.script_hashes,
+                RawText::Style => &mut csp.style_hashes,
+            };
+            if !hashes.contains(&hash) {
+                hashes.push(hash);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleElement;
+    use pretty_assertions::assert_eq;
+
+    fn script<'a>(src: Option<&'a str>, contents: &'a str) -> SimpleElement<'a, &'a str> {
+        SimpleElement {
+            tag_name: "script",
+            attributes: src.map(|src| std::iter::once(("src".into(), src.into())).collect()),
+            contents: Some(contents),
+        }
+    }
+
+    #[test]
+    fn hashes_inline_scripts_only() {
+        let mut csp = Csp::new().hash_inline();
+        let rendered = csp
+            .render((
+                script(None, "alert(1)"),
+                script(Some("/app.js"), ""),
+                script(None, "alert(1)"),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            rendered,
+            r#"<script>alert(1)</script><script src="/app.js"></script><script>alert(1)</script>"#
+        );
+        assert_eq!(
+            csp.script_hashes(),
+            ["'sha256-bhHHL3z2vDgxUt0W3dWQOrprscmda2Y5pLsLg4GF+pI='"]
+        );
+        assert!(csp.style_hashes().is_empty());
+        assert_eq!(
+            csp.header_value(),
+            "script-src 'sha256-bhHHL3z2vDgxUt0W3dWQOrprscmda2Y5pLsLg4GF+pI='"
+        );
+    }
+
+    #[test]
+    fn only_applies_inside_render() {
+        let mut csp = Csp::new().nonce("abc").unwrap();
+        assert_eq!(
+            csp.render(script(None, "")).unwrap(),
+            r#"<script nonce="abc"></script>"#
+        );
+        assert_eq!(script(None, "").render(), "<script></script>");
+        assert_eq!(
+            csp.header_value(),
+            "script-src 'nonce-abc'; style-src 'nonce-abc'"
+        );
+    }
+
+    #[test]
+    fn rejects_nonces_that_break_the_header() {
+        assert!(Csp::new().nonce("YWJj+/-_==").is_ok());
+        assert_eq!(
+            Csp::new().nonce("x' 'unsafe-inline'; script-src *"),
+            Err(InvalidNonce {
+                nonce: "x' 'unsafe-inline'; script-src *".to_owned()
+            })
+        );
+        assert!(Csp::new().nonce("").is_err());
+        assert!(Csp::new().nonce("a b").is_err());
+        assert!(Csp::new().nonce("a=b").is_err());
+    }
+}
@@ -0,0 +1,40 @@
+//! A renderable for branches with different types
+
+use crate::Render;
+use std::fmt::{Result, Write};
+
+/// Renders one of two renderables, which can have different types.
+///
+/// This is what `if` and `else` inside `rsx!` generate, so each branch can render
+/// different elements:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// let rendered = |logged_in: bool| html! {
+///     <nav>
+///         if logged_in {
+///             <a href="/logout">Log out</a>
+///         } else {
+///             <button>Log in</button>
+///         }
+///     </nav>
+/// };
+///
+/// assert_eq!(rendered(true), r#"<nav><a href="/logout">Log out</a></nav>"#);
+/// assert_eq!(rendered(false), "<nav><button>Log in</button></nav>");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<L: Render, R: Render> {
+    Left(L),
+    Right(R),
+}
+
+impl<L: Render, R: Render> Render for Either<L, R> {
+    fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        match self {
+            Self::Left(left) => left.render_into(writer),
+            Self::Right(right) => right.render_into(writer),
+        }
+    }
+}
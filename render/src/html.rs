@@ -1,9 +1,10 @@
 //! HTML utilities
 
+use crate::html_escaping::escape_comment;
 use crate::Render;
-use std::fmt::{Result, Write};
+use std::fmt::{Display, Result, Write};
 
-/// HTML 5 doctype declaration
+/// HTML 5 doctype declaration, which `<!DOCTYPE html>` inside `rsx!` generates
 ///
 /// ```rust
 /// # use pretty_assertions::assert_eq;
@@ -19,6 +20,7 @@ use std::fmt::{Result, Write};
 ///     </>
 /// };
 /// # assert_eq!(result, "<!DOCTYPE html><html><body/></html>");
+/// # assert_eq!(html! { <><!DOCTYPE html><html /></> }, "<!DOCTYPE html><html/>");
 /// ```
 #[derive(Debug)]
 pub struct HTML5Doctype;
@@ -28,3 +30,57 @@ impl Render for HTML5Doctype {
         write!(writer, "<!DOCTYPE html>")
     }
 }
+
+/// An HTML comment, like `<!--[if mso]>...<![endif]-->`.
+///
+/// The text is escaped using [`escape_comment`](../html_escaping/fn.escape_comment.html), so
+/// it can't close the comment early. This is what `<!-- ... -->` inside `rsx!` generates:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// let build = "2024.1 --> <script>";
+///
+/// let rendered = html! {
+///     <body>
+///         <!-- "[if mso]><table><![endif]" -->
+///         <!-- {build} -->
+///     </body>
+/// };
+///
+/// assert_eq!(
+///     rendered,
+///     "<body><!--[if mso]><table><![endif]--><!--2024.1 - -> <script>--></body>"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Comment<T: Display>(pub T);
+
+impl<T: Display> Render for Comment<T> {
+    fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        writer.write_str("<!--")?;
+        escape_comment(&self.0.to_string(), writer)?;
+        writer.write_str("-->")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html_escaping::{with_escape_policy, EscapePolicy, InvalidCharacters};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn follows_the_escape_policy() {
+        let policy = EscapePolicy::full()
+            .ascii_only()
+            .invalid_characters(InvalidCharacters::Strip);
+        let rendered = with_escape_policy(policy, || Comment("\u{7}> Café -\u{0}-").render());
+        assert_eq!(rendered, "<!-- > Caf&#xE9; - - -->");
+
+        let policy = EscapePolicy::full().invalid_characters(InvalidCharacters::Reject);
+        let mut buf = String::new();
+        let rejected = with_escape_policy(policy, || Comment("bell\u{7}").render_into(&mut buf));
+        assert!(rejected.is_err());
+    }
+}
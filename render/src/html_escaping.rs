@@ -1,26 +1,621 @@
+//! Escaping for the different contexts a value can be rendered in
+
+use std::cell::Cell;
 use std::fmt::{Result, Write};
 
 /// Simple HTML escaping, so strings can be safely rendered.
 ///
+/// `&`, `<`, `>`, `"` and `'` are always escaped, so the result is safe in text and in
+/// attribute values alike. The rest of the current [`EscapePolicy`](struct.EscapePolicy.html)
+/// applies, so non-ASCII and invalid characters are handled the way it says.
+///
 /// ```rust
 /// # use pretty_assertions::assert_eq;
 /// # use render::html_escaping;
+/// use render::html_escaping::{with_escape_policy, EscapePolicy};
 ///
 /// let mut buf = String::new();
 /// html_escaping::escape_html(r#"<hello world="attribute" />"#, &mut buf).unwrap();
 /// assert_eq!(buf, "&lt;hello world=&quot;attribute&quot; /&gt;");
+///
+/// let mut buf = String::new();
+/// with_escape_policy(EscapePolicy::minimal().ascii_only(), || {
+///     html_escaping::escape_html("'Café'", &mut buf)
+/// })
+/// .unwrap();
+/// assert_eq!(buf, "&apos;Caf&#xE9;&apos;");
 /// ```
 pub fn escape_html<W: Write>(html: &str, writer: &mut W) -> Result {
-    for c in html.chars() {
-        match c {
-            '>' => write!(writer, "&gt;")?,
-            '<' => write!(writer, "&lt;")?,
-            '"' => write!(writer, "&quot;")?,
-            '&' => write!(writer, "&amp;")?,
-            '\'' => write!(writer, "&apos;")?,
-            c => writer.write_char(c)?,
-        };
+    let mut context = CONTEXT.with(Cell::get);
+    context.policy.minimal = false;
+    write_escaped(html, context, false, writer)
+}
+
+/// Escapes the contents of a raw text element, like `<script>` or `<style>`.
+///
+/// These elements don't decode character references, so entity escaping would corrupt them.
+/// Instead, every `</` and `<!` gets a backslash, so the contents can't close the element
+/// or open a comment.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html_escaping;
+///
+/// let mut buf = String::new();
+/// html_escaping::escape_raw_text(r#"let a = "</script>" && 1 < 2;"#, &mut buf).unwrap();
+/// assert_eq!(buf, r#"let a = "<\/script>" && 1 < 2;"#);
+/// ```
+pub fn escape_raw_text<W: Write>(text: &str, writer: &mut W) -> Result {
+    let context = CONTEXT.with(Cell::get);
+    write_raw_text(
+        text,
+        context.raw_text.unwrap_or(RawText::Script),
+        context,
+        writer,
+    )
+}
+
+/// Escapes text for a `<script>` element, whatever element it is rendered in
+#[cfg(feature = "serde")]
+pub(crate) fn escape_script<W: Write>(text: &str, writer: &mut W) -> Result {
+    write_raw_text(text, RawText::Script, CONTEXT.with(Cell::get), writer)
+}
+
+fn write_raw_text<W: Write>(
+    text: &str,
+    raw_text: RawText,
+    context: Context,
+    writer: &mut W,
+) -> Result {
+    let policy = context.policy;
+    let mut chars = text
+        .chars()
+        .filter_map(|c| context.validate(c).transpose())
+        .peekable();
+    while let Some(c) = chars.next() {
+        let c = c?;
+        if c.is_ascii() || !policy.ascii_only {
+            writer.write_char(c)?;
+        } else if raw_text == RawText::Style {
+            write!(writer, "\\{:X} ", c as u32)?;
+        } else {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                write!(writer, "\\u{:04X}", unit)?;
+            }
+        }
+
+        if c == '<' && matches!(chars.peek(), Some(Ok('/')) | Some(Ok('!'))) {
+            writer.write_char('\\')?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Escapes the text of a comment.
+///
+/// Comments don't decode character references either. Instead, a space is added inside
+/// every `--`, so the text can't close the comment, and before a leading `>` or `->` and
+/// after a trailing `-`, which would otherwise merge with the comment's delimiters.
+///
+/// The current [`EscapePolicy`](struct.EscapePolicy.html) handles invalid characters, and an
+/// ASCII-only policy writes non-ASCII characters as numeric character references. Comments
+/// don't decode them, but aren't displayed either.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html_escaping;
+///
+/// let mut buf = String::new();
+/// html_escaping::escape_comment("-> a --> b <!-", &mut buf).unwrap();
+/// assert_eq!(buf, " -> a - -> b <!- ");
+/// ```
+pub fn escape_comment<W: Write>(text: &str, writer: &mut W) -> Result {
+    let context = CONTEXT.with(Cell::get);
+    let mut chars = text
+        .chars()
+        .filter_map(|c| context.validate(c).transpose())
+        .peekable();
+
+    let mut previous = None;
+    while let Some(c) = chars.next() {
+        let c = c?;
+        let closes = c == '>' || c == '-' && matches!(chars.peek(), Some(Ok('>')));
+        if (previous.is_none() && closes) || (c == '-' && previous == Some('-')) {
+            writer.write_char(' ')?;
+        }
+
+        if context.policy.ascii_only && !c.is_ascii() {
+            write!(writer, "&#x{:X};", c as u32)?;
+        } else {
+            writer.write_char(c)?;
+        }
+        previous = Some(c);
+    }
+
+    if previous == Some('-') {
+        writer.write_char(' ')?;
+    }
+
+    Ok(())
+}
+
+/// Escapes an attribute value, following the current [`EscapePolicy`](struct.EscapePolicy.html).
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html_escaping;
+///
+/// let mut buf = String::new();
+/// html_escaping::escape_attribute(r#"<"Tom" & 'Jerry'>"#, &mut buf).unwrap();
+/// assert_eq!(buf, "&lt;&quot;Tom&quot; &amp; &apos;Jerry&apos;&gt;");
+/// ```
+pub fn escape_attribute<W: Write>(value: &str, writer: &mut W) -> Result {
+    write_escaped(value, CONTEXT.with(Cell::get), true, writer)
+}
+
+fn write_escaped<W: Write>(
+    text: &str,
+    context: Context,
+    attribute: bool,
+    writer: &mut W,
+) -> Result {
+    let policy = context.policy;
+    let mut unescaped_from = 0;
+
+    for (index, c) in text.char_indices() {
+        let valid = context.validate(c)?;
+        if valid == Some(c) && !policy.needs_escaping(c, attribute) {
+            continue;
+        }
+
+        writer.write_str(&text[unescaped_from..index])?;
+        if let Some(c) = valid {
+            policy.write_escaped_char(c, attribute, writer)?;
+        }
+        unescaped_from = index + c.len_utf8();
+    }
+
+    writer.write_str(&text[unescaped_from..])
+}
+
+/// Escapes a URL for a URL-bearing attribute like `href` or `src`.
+///
+/// Characters that aren't allowed in URLs are percent-encoded, and the result is escaped as
+/// an attribute value.
+/// Existing percent-encoded sequences are kept as they are.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html_escaping;
+///
+/// let mut buf = String::new();
+/// html_escaping::escape_url(" /search?q=a b&lang=עב ", &mut buf).unwrap();
+/// assert_eq!(buf, "/search?q=a%20b&amp;lang=%D7%A2%D7%91");
+/// ```
+pub fn escape_url<W: Write>(url: &str, writer: &mut W) -> Result {
+    write_url(url.trim_matches(|c: char| c.is_ascii_whitespace()), writer)
+}
+
+fn write_url<W: Write>(url: &str, writer: &mut W) -> Result {
+    let mut bytes = [0; 4];
+    for c in url.chars() {
+        if c.is_ascii_graphic()
+            && !matches!(c, '"' | '<' | '>' | '\\' | '^' | '`' | '{' | '|' | '}')
+        {
+            escape_attribute(c.encode_utf8(&mut bytes), writer)?;
+        } else {
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                write!(writer, "%{:02X}", byte)?;
+            }
+        }
     }
 
     Ok(())
 }
+
+/// URL schemes that are safe to render in URL attributes. Relative URLs are always safe.
+pub const SAFE_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// The URL rendered instead of an unsafe one
+pub const INVALID_URL: &str = "about:invalid";
+
+/// Returns the URL if it is relative or uses one of the [`SAFE_URL_SCHEMES`](constant.SAFE_URL_SCHEMES.html),
+/// and [`INVALID_URL`](constant.INVALID_URL.html) otherwise.
+///
+/// The scheme is detected the way browsers do it, so leading whitespace or tabs and newlines
+/// in the middle of the scheme can't smuggle a `javascript:` URL through.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html_escaping::sanitize_url;
+/// assert_eq!(sanitize_url("https://example.com"), "https://example.com");
+/// assert_eq!(sanitize_url("../profile?tab=posts"), "../profile?tab=posts");
+/// assert_eq!(sanitize_url("MAILTO:me@example.com"), "MAILTO:me@example.com");
+/// assert_eq!(sanitize_url(" java\tscript:alert(1)"), "about:invalid");
+/// assert_eq!(sanitize_url("data:text/html,<script>alert(1)</script>"), "about:invalid");
+/// ```
+pub fn sanitize_url(url: &str) -> &str {
+    match url_start(url) {
+        UrlStart::Scheme { safe: false } => INVALID_URL,
+        _ => url,
+    }
+}
+
+/// What the start of a URL tells about its scheme
+enum UrlStart {
+    Scheme {
+        safe: bool,
+    },
+    Relative,
+    /// The URL may still turn out to have a scheme, if more of it follows
+    Unknown,
+}
+
+fn url_start(url: &str) -> UrlStart {
+    let scheme = url
+        .trim_start_matches(|c: char| c <= ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'));
+
+    match scheme.clone().next() {
+        Some(c) if c.is_ascii_alphabetic() => {}
+        Some(_) => return UrlStart::Relative,
+        None => return UrlStart::Unknown,
+    }
+
+    let length = scheme
+        .clone()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        .count();
+    match scheme.clone().nth(length) {
+        Some(':') => UrlStart::Scheme {
+            safe: SAFE_URL_SCHEMES.iter().any(|safe| {
+                safe.len() == length
+                    && safe
+                        .chars()
+                        .zip(scheme.clone())
+                        .all(|(safe, c)| safe.eq_ignore_ascii_case(&c))
+            }),
+        },
+        Some(_) => UrlStart::Relative,
+        None => UrlStart::Unknown,
+    }
+}
+
+/// Sanitizes and escapes a URL as it is written into it, like
+/// [`sanitize_url`](fn.sanitize_url.html) and [`escape_url`](fn.escape_url.html) do, without
+/// collecting it into a `String` first. Call `finish` once the whole URL is written.
+pub(crate) struct SanitizeUrl<'w, W: Write> {
+    writer: &'w mut W,
+    start: UrlStart,
+    /// Text that can't be written yet: the start of the URL while its scheme is unknown, or
+    /// whitespace that is trimmed if nothing follows it
+    pending: String,
+}
+
+impl<'w, W: Write> SanitizeUrl<'w, W> {
+    pub(crate) fn new(writer: &'w mut W) -> Self {
+        Self {
+            writer,
+            start: UrlStart::Unknown,
+            pending: String::new(),
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> Result {
+        if let UrlStart::Unknown = self.start {
+            let url = std::mem::take(&mut self.pending);
+            write_url(
+                url.trim_matches(|c: char| c.is_ascii_whitespace()),
+                self.writer,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the URL, keeping the whitespace at its end until more of it follows
+    fn write_trimming_end(&mut self, url: &str) -> Result {
+        let trimmed = url.trim_end_matches(|c: char| c.is_ascii_whitespace());
+        if !trimmed.is_empty() {
+            write_url(&self.pending, self.writer)?;
+            self.pending.clear();
+            write_url(trimmed, self.writer)?;
+        }
+        self.pending.push_str(&url[trimmed.len()..]);
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for SanitizeUrl<'_, W> {
+    fn write_str(&mut self, url: &str) -> Result {
+        match self.start {
+            UrlStart::Scheme { safe: false } => return Ok(()),
+            UrlStart::Scheme { safe: true } | UrlStart::Relative => {
+                return self.write_trimming_end(url)
+            }
+            UrlStart::Unknown => {}
+        }
+
+        // Most URLs are written in one piece, so they don't need to be buffered
+        let start = if self.pending.is_empty() {
+            url_start(url)
+        } else {
+            self.pending.push_str(url);
+            url_start(&self.pending)
+        };
+
+        let url = match start {
+            UrlStart::Unknown => {
+                if self.pending.is_empty() {
+                    self.pending.push_str(url);
+                }
+                return Ok(());
+            }
+            UrlStart::Scheme { safe: false } => {
+                self.start = start;
+                return escape_attribute(INVALID_URL, self.writer);
+            }
+            _ if self.pending.is_empty() => url,
+            _ => &std::mem::take(&mut self.pending),
+        };
+        self.start = start;
+        self.write_trimming_end(url.trim_start_matches(|c: char| c.is_ascii_whitespace()))
+    }
+}
+
+/// Escapes text using the rules of the element it is rendered in.
+///
+/// Text is HTML escaped following the current [`EscapePolicy`](struct.EscapePolicy.html),
+/// unless it is rendered inside a raw text element like `<script>` or `<style>`, where it is
+/// escaped using [`escape_raw_text`](fn.escape_raw_text.html).
+/// This is what the `Render` implementations for strings use.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// let rendered = html! {
+///     <>
+///         <p>{"</p>"}</p>
+///         <script>{"if (a < b) { alert('</script>') }"}</script>
+///     </>
+/// };
+///
+/// assert_eq!(
+///     rendered,
+///     r#"<p>&lt;/p&gt;</p><script>if (a < b) { alert('<\/script>') }</script>"#
+/// );
+/// ```
+pub fn escape_text<W: Write>(text: &str, writer: &mut W) -> Result {
+    let context = CONTEXT.with(Cell::get);
+    match context.raw_text {
+        Some(raw_text) => write_raw_text(text, raw_text, context, writer),
+        None => write_escaped(text, context, false, writer),
+    }
+}
+
+/// Which characters are escaped in text and attribute values.
+///
+/// By default, `&`, `<`, `>`, `"` and `'` are escaped everywhere. The minimal policy escapes
+/// only what is needed to keep the markup intact: `&` and `<` in text, and `&` and `"` in
+/// attribute values, which are always double-quoted. Either can be made ASCII-only, which
+/// encodes every non-ASCII character as a numeric character reference.
+///
+/// A policy applies to everything rendered inside [`with_escape_policy`](fn.with_escape_policy.html):
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::{rsx, Render};
+/// use render::html_escaping::{with_escape_policy, EscapePolicy};
+///
+/// let tree = || rsx! { <p title={"\"Café\" > 'Bar'"}>{"Café > 'Bar'"}</p> };
+///
+/// assert_eq!(
+///     with_escape_policy(EscapePolicy::minimal(), || tree().render()),
+///     r#"<p title="&quot;Café&quot; > 'Bar'">Café > 'Bar'</p>"#
+/// );
+/// assert_eq!(
+///     with_escape_policy(EscapePolicy::full().ascii_only(), || tree().render()),
+///     r#"<p title="&quot;Caf&#xE9;&quot; &gt; &apos;Bar&apos;">Caf&#xE9; &gt; &apos;Bar&apos;</p>"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscapePolicy {
+    minimal: bool,
+    ascii_only: bool,
+    invalid_characters: InvalidCharacters,
+}
+
+impl EscapePolicy {
+    /// Escapes `&`, `<`, `>`, `"` and `'` everywhere
+    pub const fn full() -> Self {
+        Self {
+            minimal: false,
+            ascii_only: false,
+            invalid_characters: InvalidCharacters::Keep,
+        }
+    }
+
+    /// Escapes `&` and `<` in text, and `&` and `"` in attribute values
+    pub const fn minimal() -> Self {
+        Self {
+            minimal: true,
+            ascii_only: false,
+            invalid_characters: InvalidCharacters::Keep,
+        }
+    }
+
+    /// Encodes every non-ASCII character as well. Inside `<script>` and `<style>` elements,
+    /// JavaScript and CSS escapes are used instead of character references.
+    pub const fn ascii_only(self) -> Self {
+        Self {
+            ascii_only: true,
+            ..self
+        }
+    }
+
+    /// Sets how characters that are invalid in HTML are handled. They are kept by default.
+    pub const fn invalid_characters(self, invalid_characters: InvalidCharacters) -> Self {
+        Self {
+            invalid_characters,
+            ..self
+        }
+    }
+
+    /// The character to write instead of `c`, if any
+    fn validate(&self, c: char) -> std::result::Result<Option<char>, std::fmt::Error> {
+        if !is_invalid_character(c) {
+            return Ok(Some(c));
+        }
+
+        match self.invalid_characters {
+            InvalidCharacters::Keep => Ok(Some(c)),
+            InvalidCharacters::Strip => Ok(None),
+            InvalidCharacters::Replace => Ok(Some(char::REPLACEMENT_CHARACTER)),
+            InvalidCharacters::Reject => Err(std::fmt::Error),
+        }
+    }
+
+    fn entity(&self, c: char, attribute: bool) -> Option<&'static str> {
+        match c {
+            '&' => Some("&amp;"),
+            '<' if !(self.minimal && attribute) => Some("&lt;"),
+            '>' if !self.minimal => Some("&gt;"),
+            '"' if !self.minimal || attribute => Some("&quot;"),
+            '\'' if !self.minimal => Some("&apos;"),
+            _ => None,
+        }
+    }
+
+    fn needs_escaping(&self, c: char, attribute: bool) -> bool {
+        self.entity(c, attribute).is_some() || (self.ascii_only && !c.is_ascii())
+    }
+
+    fn write_escaped_char<W: Write>(&self, c: char, attribute: bool, writer: &mut W) -> Result {
+        match self.entity(c, attribute) {
+            Some(entity) => writer.write_str(entity),
+            None if self.ascii_only && !c.is_ascii() => write!(writer, "&#x{:X};", c as u32),
+            None => writer.write_char(c),
+        }
+    }
+}
+
+/// How to handle characters that the HTML spec treats as parse errors: control characters
+/// other than ASCII whitespace, including NUL, and Unicode noncharacters.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::{html, Render};
+/// use render::html_escaping::{with_escape_policy, EscapePolicy, InvalidCharacters};
+///
+/// let policy = |invalid| EscapePolicy::full().invalid_characters(invalid);
+/// let text = "bell\u{7}\u{FFFF}";
+///
+/// let stripped = with_escape_policy(policy(InvalidCharacters::Strip), || text.render());
+/// assert_eq!(stripped, "bell");
+///
+/// let replaced = with_escape_policy(policy(InvalidCharacters::Replace), || text.render());
+/// assert_eq!(replaced, "bell\u{FFFD}\u{FFFD}");
+///
+/// let mut buf = String::new();
+/// let rejected = with_escape_policy(policy(InvalidCharacters::Reject), || {
+///     text.render_into(&mut buf)
+/// });
+/// assert!(rejected.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidCharacters {
+    /// Writes them as they are
+    #[default]
+    Keep,
+    /// Removes them
+    Strip,
+    /// Replaces each of them with U+FFFD REPLACEMENT CHARACTER
+    Replace,
+    /// Fails rendering with a `fmt::Error`
+    Reject,
+}
+
+/// The Unicode embedding, override and isolate controls, which change the direction of the
+/// text after them
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+fn is_invalid_character(c: char) -> bool {
+    let is_noncharacter = matches!(c, '\u{FDD0}'..='\u{FDEF}') || (c as u32) & 0xFFFE == 0xFFFE;
+    (c.is_control() && !c.is_ascii_whitespace()) || is_noncharacter
+}
+
+impl Default for EscapePolicy {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+/// Runs `f`, escaping everything that is rendered inside it using `policy`
+pub fn with_escape_policy<R>(policy: EscapePolicy, f: impl FnOnce() -> R) -> R {
+    with_context(|context| context.policy = policy, f)
+}
+
+/// Raw text elements, whose contents are escaped using their own language's escapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RawText {
+    Script,
+    Style,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Context {
+    raw_text: Option<RawText>,
+    policy: EscapePolicy,
+    /// Whether bidi controls are removed, so the text can't leave its isolate
+    isolated: bool,
+}
+
+impl Context {
+    /// The character to write instead of `c`, if any
+    fn validate(&self, c: char) -> std::result::Result<Option<char>, std::fmt::Error> {
+        if self.isolated && is_bidi_control(c) {
+            return Ok(None);
+        }
+        self.policy.validate(c)
+    }
+}
+
+thread_local! {
+    static CONTEXT: Cell<Context> = const {
+        Cell::new(Context {
+            raw_text: None,
+            policy: EscapePolicy::full(),
+            isolated: false,
+        })
+    };
+}
+
+struct RestoreContext(Context);
+
+impl Drop for RestoreContext {
+    fn drop(&mut self) {
+        CONTEXT.with(|context| context.set(self.0));
+    }
+}
+
+fn with_context<R>(update: impl FnOnce(&mut Context), f: impl FnOnce() -> R) -> R {
+    let previous = CONTEXT.with(Cell::get);
+    let _restore = RestoreContext(previous);
+    let mut context = previous;
+    update(&mut context);
+    CONTEXT.with(|current| current.set(context));
+    f()
+}
+
+/// Runs `f` with text escaped for a raw text element, or for regular HTML
+pub(crate) fn with_raw_text<R>(raw_text: Option<RawText>, f: impl FnOnce() -> R) -> R {
+    with_context(|context| context.raw_text = raw_text, f)
+}
+
+/// Runs `f` with bidi controls removed from the text, for content inside an isolate
+pub(crate) fn with_isolated_direction<R>(f: impl FnOnce() -> R) -> R {
+    with_context(|context| context.isolated = true, f)
+}
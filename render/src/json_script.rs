@@ -0,0 +1,197 @@
+//! Embedding JSON in script elements
+
+use crate::html_escaping::{escape_attribute, escape_script};
+use crate::Render;
+use serde::Serialize;
+use std::fmt::{Result, Write};
+use std::{io, str};
+
+/// Serializes a value into a `<script type="application/json">` element.
+///
+/// `<`, `>`, `&`, U+2028 and U+2029 are written as `\u` escapes, so a string in the value
+/// can't close the script element, and the contents stay valid JSON and valid JavaScript.
+/// The current [`EscapePolicy`](html_escaping/struct.EscapePolicy.html) applies as it does
+/// inside `<script>`: an ASCII-only policy writes non-ASCII characters as `\u` escapes, and
+/// invalid characters are handled the way it says.
+/// The JSON is written straight into the render writer.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// use render::JsonScript;
+/// use std::collections::BTreeMap;
+///
+/// let mut state = BTreeMap::new();
+/// state.insert("user", "</script><script>steal()</script>");
+///
+/// let rendered = html! { <body>{JsonScript::new(&state)}</body> };
+/// assert_eq!(
+///     rendered,
+///     concat!(
+///         r#"<body><script type="application/json">"#,
+///         r#"{"user":"\u003c/script\u003e\u003cscript\u003esteal()\u003c/script\u003e"}"#,
+///         "</script></body>"
+///     )
+/// );
+/// ```
+#[derive(Debug)]
+pub struct JsonScript<'a, T: Serialize + ?Sized> {
+    value: &'a T,
+    script_type: &'a str,
+    id: Option<&'a str>,
+}
+
+impl<'a, T: Serialize + ?Sized> JsonScript<'a, T> {
+    pub fn new(value: &'a T) -> Self {
+        Self {
+            value,
+            script_type: "application/json",
+            id: None,
+        }
+    }
+
+    /// Sets the `id` attribute, so scripts can find the element
+    pub fn id(mut self, id: &'a str) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Renders the value as JSON-LD structured data, with `type="application/ld+json"`
+    pub fn json_ld(mut self) -> Self {
+        self.script_type = "application/ld+json";
+        self
+    }
+}
+
+impl<T: Serialize + ?Sized> Render for JsonScript<'_, T> {
+    fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        writer.write_str("<script type=\"")?;
+        escape_attribute(self.script_type, writer)?;
+        if let Some(id) = self.id {
+            writer.write_str("\" id=\"")?;
+            escape_attribute(id, writer)?;
+        }
+        writer.write_str("\">")?;
+        Json(self.value).render_into(writer)?;
+        writer.write_str("</script>")
+    }
+}
+
+/// A value serialized as script-safe JSON
+struct Json<'a, T: ?Sized>(&'a T);
+
+impl<T: Serialize + ?Sized> Render for Json<'_, T> {
+    fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        let mut json_writer = JsonWriter {
+            writer,
+            incomplete: Vec::new(),
+        };
+        serde_json::to_writer(&mut json_writer, self.0).map_err(|_| std::fmt::Error)?;
+
+        if json_writer.incomplete.is_empty() {
+            Ok(())
+        } else {
+            Err(std::fmt::Error)
+        }
+    }
+}
+
+/// Adapts a `fmt::Write` into the `io::Write` serde_json writes into, escaping the JSON
+/// on the way
+struct JsonWriter<'w, W: Write> {
+    writer: &'w mut W,
+    /// The start of a UTF-8 sequence that was split between writes
+    incomplete: Vec<u8>,
+}
+
+impl<W: Write> JsonWriter<'_, W> {
+    fn write_str(&mut self, json: &str) -> io::Result<()> {
+        escape_json(json, self.writer).map_err(|_| io::Error::other("failed writing JSON"))
+    }
+}
+
+impl<W: Write> io::Write for JsonWriter<'_, W> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        if self.incomplete.is_empty() {
+            if let Ok(json) = str::from_utf8(bytes) {
+                self.write_str(json)?;
+                return Ok(bytes.len());
+            }
+        }
+
+        let mut buffered = std::mem::take(&mut self.incomplete);
+        buffered.extend_from_slice(bytes);
+        let valid_up_to = match str::from_utf8(&buffered) {
+            Ok(json) => json.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+        };
+
+        let (valid, incomplete) = buffered.split_at(valid_up_to);
+        self.write_str(str::from_utf8(valid).expect("checked above"))?;
+        self.incomplete = incomplete.to_vec();
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Escapes the characters that are unsafe in a script element, and applies the escape policy.
+/// They can only appear inside JSON strings, where a `\u` escape means the same thing.
+fn escape_json<W: Write>(json: &str, writer: &mut W) -> Result {
+    let mut unescaped_from = 0;
+
+    for (index, c) in json.char_indices() {
+        if matches!(c, '<' | '>' | '&' | '\u{2028}' | '\u{2029}') {
+            escape_script(&json[unescaped_from..index], writer)?;
+            write!(writer, "\\u{:04x}", c as u32)?;
+            unescaped_from = index + c.len_utf8();
+        }
+    }
+
+    escape_script(&json[unescaped_from..], writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write as _;
+
+    #[test]
+    fn escapes_unsafe_characters() {
+        let rendered = Json("a&b\u{2028}\u{2029}<!--").render();
+        assert_eq!(rendered, r#""a\u0026b\u2028\u2029\u003c!--""#);
+    }
+
+    #[test]
+    fn follows_the_escape_policy() {
+        use crate::html_escaping::{with_escape_policy, EscapePolicy, InvalidCharacters};
+
+        let policy = EscapePolicy::full().ascii_only();
+        let rendered = with_escape_policy(policy, || Json("Café 🦀").render());
+        assert_eq!(rendered, r#""Caf\u00E9 \uD83E\uDD80""#);
+
+        let policy = EscapePolicy::full().invalid_characters(InvalidCharacters::Strip);
+        let rendered = with_escape_policy(policy, || Json("a\u{FFFF}b").render());
+        assert_eq!(rendered, r#""ab""#);
+    }
+
+    #[test]
+    fn buffers_split_utf8_sequences() {
+        let mut buf = String::new();
+        let mut writer = JsonWriter {
+            writer: &mut buf,
+            incomplete: Vec::new(),
+        };
+        let bytes = "\"ש\u{2028}\"".as_bytes();
+        for byte in bytes {
+            writer.write_all(std::slice::from_ref(byte)).unwrap();
+        }
+
+        assert!(writer.incomplete.is_empty());
+        assert_eq!(buf, r#""ש\u2028""#);
+    }
+}
@@ -104,9 +104,9 @@
 //! If you pay close attention, you see that the function `Heading` is:
 //!
 //! * declared with an uppercase. Underneath, it generates a struct with the same name, and
-//! implements the `Render` trait on it.
+//!   implements the `Render` trait on it.
 //! * does not have a return type. This is because everything is written to a writer, for
-//! performance reasons.
+//!   performance reasons.
 //!
 //! ### Full example
 //!
@@ -164,16 +164,34 @@
 //! # assert_eq!(actual, expected);
 //! ```
 
+#[cfg(feature = "assets")]
+pub mod assets;
+mod attribute_value;
+mod class_list;
+#[cfg(feature = "csp")]
+pub mod csp;
+mod either;
 pub mod fragment;
 pub mod html;
 pub mod html_escaping;
+#[cfg(feature = "serde")]
+mod json_script;
 mod numbers;
 mod render;
+pub mod sanitize;
+mod scope;
 mod simple_element;
+pub mod style;
 mod text_element;
 
 pub use self::render::Render;
+pub use attribute_value::{AttributeValue, TrustedScript, TrustedUrl};
+pub use class_list::{ClassList, ClassNames};
+pub use either::Either;
 pub use fragment::Fragment;
+#[cfg(feature = "serde")]
+pub use json_script::JsonScript;
 pub use render_macros::{component, html, rsx};
-pub use simple_element::SimpleElement;
-pub use text_element::Raw;
+pub use scope::Scope;
+pub use simple_element::{spread_attributes, SimpleElement};
+pub use text_element::{Isolate, Raw};
@@ -0,0 +1,520 @@
+//! Sanitizing untrusted HTML
+
+use crate::html_escaping::escape_text;
+use crate::simple_element::{is_one_of, write_attribute};
+use crate::{AttributeValue, Render};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Result, Write};
+
+/// Renders untrusted HTML, keeping only the tags and attributes a [`Policy`](struct.Policy.html)
+/// allows.
+///
+/// Disallowed tags are removed but their text is kept, except for elements like `<script>`
+/// whose contents are removed as well. Comments and doctypes are always removed. Attributes go
+/// through the same escaping as [`SimpleElement`](../struct.SimpleElement.html) attributes,
+/// so URLs with unsafe schemes and event handlers never make it to the output.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// use render::sanitize::{Policy, Sanitized};
+///
+/// let comment = r#"<p onclick="steal()">Nice <b>post</b>!<script>steal()</script></p>
+/// <a href="javascript:steal()">me</a><img src="x">"#;
+/// let policy = Policy::default();
+///
+/// let rendered = html! {
+///     <div class={"comment"}>
+///         <Sanitized html={comment} policy={&policy} />
+///     </div>
+/// };
+///
+/// assert_eq!(
+///     rendered,
+///     concat!(
+///         r#"<div class="comment"><p>Nice <b>post</b>!</p>"#,
+///         "\n",
+///         r#"<a href="about:invalid">me</a></div>"#
+///     )
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Sanitized<'a> {
+    pub html: &'a str,
+    pub policy: &'a Policy,
+}
+
+/// The tags and attributes [`Sanitized`](struct.Sanitized.html) keeps.
+///
+/// `Policy::new()` keeps nothing but text, and `Policy::default()` keeps basic formatting,
+/// lists, quotes, code and links.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::Render;
+/// use render::sanitize::{Policy, Sanitized};
+///
+/// let policy = Policy::new()
+///     .allow_tags(&["p"])
+///     .allow_attributes("img", &["src"])
+///     .allow_global_attributes(&["lang"]);
+///
+/// let html = r#"<p lang="he" class="x">שלום <img src="/wave.png" onerror="x()"></p>"#;
+/// let rendered = Sanitized { html, policy: &policy }.render();
+///
+/// assert_eq!(rendered, r#"<p lang="he">שלום <img src="/wave.png"/></p>"#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy {
+    tags: HashMap<String, HashSet<String>>,
+    global_attributes: HashSet<String>,
+}
+
+/// Elements that are removed along with their contents
+const DROPPED_ELEMENTS: &[&str] = &[
+    "iframe", "noembed", "noframes", "noscript", "object", "script", "style", "template",
+    "textarea", "title", "xmp",
+];
+
+/// Elements that can't have contents
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+impl Policy {
+    pub fn new() -> Self {
+        Self {
+            tags: HashMap::new(),
+            global_attributes: HashSet::new(),
+        }
+    }
+
+    /// Keeps these tags, without any of their attributes
+    pub fn allow_tags(mut self, tags: &[&str]) -> Self {
+        for tag in tags {
+            self.tags.entry(tag.to_ascii_lowercase()).or_default();
+        }
+        self
+    }
+
+    /// Keeps this tag along with these attributes
+    pub fn allow_attributes(mut self, tag: &str, attributes: &[&str]) -> Self {
+        let allowed = self.tags.entry(tag.to_ascii_lowercase()).or_default();
+        allowed.extend(attributes.iter().map(|name| name.to_ascii_lowercase()));
+        self
+    }
+
+    /// Keeps these attributes on every allowed tag
+    pub fn allow_global_attributes(mut self, attributes: &[&str]) -> Self {
+        let attributes = attributes.iter().map(|name| name.to_ascii_lowercase());
+        self.global_attributes.extend(attributes);
+        self
+    }
+
+    fn allows_tag(&self, tag: &str) -> bool {
+        self.tags.contains_key(tag)
+    }
+
+    fn allows_attribute(&self, tag: &str, attribute: &str) -> bool {
+        self.global_attributes.contains(attribute)
+            || self
+                .tags
+                .get(tag)
+                .is_some_and(|allowed| allowed.contains(attribute))
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::new()
+            .allow_tags(&[
+                "b",
+                "blockquote",
+                "br",
+                "code",
+                "del",
+                "em",
+                "hr",
+                "i",
+                "li",
+                "ol",
+                "p",
+                "pre",
+                "s",
+                "strong",
+                "sub",
+                "sup",
+                "u",
+                "ul",
+            ])
+            .allow_attributes("a", &["href", "title"])
+            .allow_attributes("abbr", &["title"])
+    }
+}
+
+impl Render for Sanitized<'_> {
+    fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        let mut open_tags: Vec<String> = vec![];
+        let mut tokens = Tokenizer { html: self.html };
+
+        while let Some(token) = tokens.next() {
+            match token {
+                Token::Text(text) => write_decoded(text, writer)?,
+                Token::StartTag { name, attributes } => {
+                    if is_one_of(&name, DROPPED_ELEMENTS) {
+                        tokens.skip_element(&name);
+                    } else if self.policy.allows_tag(&name) {
+                        write!(writer, "<{}", name)?;
+                        let mut written: Vec<String> = vec![];
+                        for (key, value) in attributes {
+                            // Like browsers, the first of repeated attributes wins
+                            if !self.policy.allows_attribute(&name, &key)
+                                || written.iter().any(|seen| seen.eq_ignore_ascii_case(&key))
+                            {
+                                continue;
+                            }
+                            write_attribute(
+                                &key,
+                                AttributeValue::from(decode(value).as_str()),
+                                writer,
+                            )?;
+                            written.push(key);
+                        }
+                        if is_one_of(&name, VOID_ELEMENTS) {
+                            write!(writer, "/>")?;
+                        } else {
+                            write!(writer, ">")?;
+                            open_tags.push(name);
+                        }
+                    }
+                }
+                Token::EndTag { name } => {
+                    if let Some(index) = open_tags.iter().rposition(|tag| *tag == name) {
+                        for tag in open_tags.drain(index..).rev() {
+                            write!(writer, "</{}>", tag)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        for tag in open_tags.into_iter().rev() {
+            write!(writer, "</{}>", tag)?;
+        }
+
+        Ok(())
+    }
+}
+
+enum Token<'h> {
+    Text(&'h str),
+    StartTag {
+        name: String,
+        attributes: Vec<(String, &'h str)>,
+    },
+    EndTag {
+        name: String,
+    },
+}
+
+/// A forgiving HTML tokenizer, which only recognizes what the sanitizer needs
+struct Tokenizer<'h> {
+    html: &'h str,
+}
+
+impl<'h> Iterator for Tokenizer<'h> {
+    type Item = Token<'h>;
+
+    fn next(&mut self) -> Option<Token<'h>> {
+        loop {
+            if self.html.is_empty() {
+                return None;
+            }
+
+            if !self.html.starts_with('<') {
+                let end = self.html.find('<').unwrap_or(self.html.len());
+                return Some(Token::Text(self.advance(end)));
+            }
+
+            let rest = &self.html[1..];
+            if let Some(comment) = rest.strip_prefix("!--") {
+                let end = comment.find("-->").map_or(self.html.len(), |i| i + 7);
+                self.advance(end);
+            } else if rest.starts_with(['!', '?']) {
+                let end = self.html.find('>').map_or(self.html.len(), |i| i + 1);
+                self.advance(end);
+            } else if rest.starts_with('/')
+                && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                self.advance(2);
+                let name = self.tag_name();
+                let end = self.html.find('>').map_or(self.html.len(), |i| i + 1);
+                self.advance(end);
+                return Some(Token::EndTag { name });
+            } else if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                self.advance(1);
+                let name = self.tag_name();
+                let attributes = self.attributes()?;
+                return Some(Token::StartTag { name, attributes });
+            } else {
+                return Some(Token::Text(self.advance(1)));
+            }
+        }
+    }
+}
+
+impl<'h> Tokenizer<'h> {
+    fn advance(&mut self, len: usize) -> &'h str {
+        let (consumed, rest) = self.html.split_at(len);
+        self.html = rest;
+        consumed
+    }
+
+    fn tag_name(&mut self) -> String {
+        let end = self
+            .html
+            .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+            .unwrap_or(self.html.len());
+        self.advance(end).to_ascii_lowercase()
+    }
+
+    /// Parses attributes up to the end of the tag. Unterminated tags are dropped.
+    fn attributes(&mut self) -> Option<Vec<(String, &'h str)>> {
+        let mut attributes = vec![];
+
+        loop {
+            self.html = self
+                .html
+                .trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+            if self.html.is_empty() {
+                return None;
+            }
+            if self.html.starts_with('>') {
+                self.advance(1);
+                return Some(attributes);
+            }
+
+            // The first character is part of the name, even if it is a `=`
+            let first_len = self.html.chars().next().map_or(0, char::len_utf8);
+            let name_end = self.html[first_len..]
+                .find(|c: char| c.is_ascii_whitespace() || matches!(c, '/' | '>' | '='))
+                .map_or(self.html.len(), |i| i + first_len);
+            let name = self.advance(name_end).to_ascii_lowercase();
+
+            self.html = self
+                .html
+                .trim_start_matches(|c: char| c.is_ascii_whitespace());
+            let value = if self.html.starts_with('=') {
+                self.advance(1);
+                self.html = self
+                    .html
+                    .trim_start_matches(|c: char| c.is_ascii_whitespace());
+                self.attribute_value()?
+            } else {
+                ""
+            };
+
+            if !attributes.iter().any(|(existing, _)| *existing == name) {
+                attributes.push((name, value));
+            }
+        }
+    }
+
+    fn attribute_value(&mut self) -> Option<&'h str> {
+        match self.html.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => {
+                let end = self.html[1..].find(quote)? + 1;
+                let value = &self.html[1..end];
+                self.advance(end + 1);
+                Some(value)
+            }
+            _ => {
+                let end = self
+                    .html
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or(self.html.len());
+                Some(self.advance(end))
+            }
+        }
+    }
+
+    /// Skips everything up to and including the end tag of a dropped element
+    fn skip_element(&mut self, name: &str) {
+        loop {
+            match self.next() {
+                None => return,
+                Some(Token::EndTag { name: end }) if end == name => return,
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Named character references that are decoded. Others are rendered as text.
+const NAMED_REFERENCES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("apos", '\''),
+    ("bull", '•'),
+    ("copy", '©'),
+    ("deg", '°'),
+    ("euro", '€'),
+    ("gt", '>'),
+    ("hellip", '…'),
+    ("laquo", '«'),
+    ("ldquo", '“'),
+    ("lsquo", '‘'),
+    ("lt", '<'),
+    ("mdash", '—'),
+    ("middot", '·'),
+    ("nbsp", '\u{a0}'),
+    ("ndash", '–'),
+    ("quot", '"'),
+    ("raquo", '»'),
+    ("rdquo", '”'),
+    ("reg", '®'),
+    ("rsquo", '’'),
+    ("shy", '\u{ad}'),
+    ("times", '×'),
+    ("trade", '™'),
+];
+
+fn decode_reference(reference: &str) -> Option<char> {
+    if let Some(number) = reference.strip_prefix('#') {
+        let code = match number.strip_prefix(|c| c == 'x' || c == 'X') {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return match std::char::from_u32(code) {
+            Some('\0') | None => Some('\u{fffd}'),
+            Some(c) => Some(c),
+        };
+    }
+
+    NAMED_REFERENCES
+        .iter()
+        .find(|(name, _)| *name == reference)
+        .map(|(_, c)| *c)
+}
+
+fn decode(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let reference = rest[1..]
+            .find(';')
+            .map(|end| &rest[1..=end])
+            .filter(|reference| {
+                reference
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '#')
+            });
+
+        match reference.and_then(decode_reference) {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[reference.unwrap().len() + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+fn write_decoded<W: Write>(text: &str, writer: &mut W) -> Result {
+    if text.contains('&') {
+        escape_text(&decode(text), writer)
+    } else {
+        escape_text(text, writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn sanitize(html: &str) -> String {
+        Sanitized {
+            html,
+            policy: &Policy::default(),
+        }
+        .render()
+    }
+
+    #[test]
+    fn keeps_text_of_disallowed_tags() {
+        assert_eq!(
+            sanitize("<div><h1>Title</h1><p>Body</p></div>"),
+            "Title<p>Body</p>"
+        );
+    }
+
+    #[test]
+    fn drops_dangerous_elements_with_their_contents() {
+        assert_eq!(
+            sanitize("a<script>alert('<p>')</script>b<STYLE>*{}</STYLE>c<!-- <b> -->d"),
+            "abcd"
+        );
+    }
+
+    #[test]
+    fn balances_tags() {
+        assert_eq!(sanitize("<b><i>x</b>y</i></u>"), "<b><i>x</i></b>y");
+        assert_eq!(sanitize("<ul><li>unclosed"), "<ul><li>unclosed</li></ul>");
+    }
+
+    #[test]
+    fn decodes_and_escapes_text_and_attributes() {
+        assert_eq!(
+            sanitize("1 &lt; 2 &amp;&amp; &copy; &unknown; & <3"),
+            "1 &lt; 2 &amp;&amp; © &amp;unknown; &amp; &lt;3"
+        );
+        assert_eq!(
+            sanitize(r#"<a href="jav&#x61;script:alert(1)">y</a>"#),
+            r#"<a href="about:invalid">y</a>"#
+        );
+        assert_eq!(
+            sanitize(r#"<abbr title='"x" &amp; y'>z</abbr>"#),
+            r#"<abbr title="&quot;x&quot; &amp; y">z</abbr>"#
+        );
+        assert_eq!(
+            sanitize(r#"<a href=&#x6a;avascript:alert(1)>y</a>"#),
+            r#"<a href="about:invalid">y</a>"#
+        );
+    }
+
+    #[test]
+    fn keeps_attributes_in_order() {
+        let policy = Policy::new().allow_attributes("a", &["href", "title", "rel"]);
+        let html = r#"<a title="t" rel=nofollow href="/x" TITLE="u">y</a>"#;
+        assert_eq!(
+            Sanitized {
+                html,
+                policy: &policy
+            }
+            .render(),
+            r#"<a title="t" rel="nofollow" href="/x">y</a>"#
+        );
+    }
+
+    #[test]
+    fn handles_non_ascii_text_and_attributes() {
+        assert_eq!(sanitize("שלום <b עולם=x>עולם</b>"), "שלום <b>עולם</b>");
+    }
+
+    #[test]
+    fn drops_unterminated_tags() {
+        assert_eq!(sanitize("text<b class=\"x"), "text");
+        assert_eq!(sanitize("a < b"), "a &lt; b");
+    }
+}
@@ -0,0 +1,37 @@
+//! A renderable for local bindings
+
+use crate::Render;
+use std::fmt::{Result, Write};
+
+/// Renders by calling a function with the writer, so values computed inside it can be
+/// borrowed by what it renders.
+///
+/// This is what `let` statements and `for` loops inside `rsx!` generate. A loop renders
+/// every item's children before taking the next item, and a binding is visible to the
+/// siblings that follow it:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// let cents = 1999;
+///
+/// let rendered = html! {
+///     <p>
+///         let price = format!("${}.{:02}", cents / 100, cents % 100);
+///         <button aria-label={price.as_str()}>{price.as_str()}</button>
+///     </p>
+/// };
+///
+/// assert_eq!(rendered, r#"<p><button aria-label="$19.99">$19.99</button></p>"#);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scope<F>(pub F);
+
+impl<F> Render for Scope<F>
+where
+    F: FnOnce(&mut dyn Write) -> Result,
+{
+    fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        (self.0)(writer)
+    }
+}
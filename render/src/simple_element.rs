@@ -1,10 +1,40 @@
-use crate::html_escaping::escape_html;
+use crate::html_escaping::{
+    escape_attribute, escape_url, sanitize_url, with_raw_text, RawText, SanitizeUrl,
+};
+use crate::AttributeValue;
 use crate::Render;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Result, Write};
 
-type Attributes<'a> = Option<HashMap<&'a str, Cow<'a, str>>>;
+pub(crate) type Attributes<'a> = Option<HashMap<Cow<'a, str>, AttributeValue<'a>>>;
+
+/// Attributes whose value is a single URL, which are sanitized and percent-encoded
+/// before being escaped
+const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "codebase",
+    "formaction",
+    "href",
+    "longdesc",
+    "manifest",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+/// Elements whose text content is not parsed as HTML
+fn raw_text_element(tag_name: &str) -> Option<RawText> {
+    if tag_name.eq_ignore_ascii_case("script") {
+        Some(RawText::Script)
+    } else if tag_name.eq_ignore_ascii_case("style") {
+        Some(RawText::Style)
+    } else {
+        None
+    }
+}
 
 /// Simple HTML element tag
 #[derive(Debug)]
@@ -15,35 +45,335 @@ pub struct SimpleElement<'a, T: Render> {
     pub contents: Option<T>,
 }
 
-fn write_attributes<'a, W: Write>(maybe_attributes: Attributes<'a>, writer: &mut W) -> Result {
+pub(crate) fn is_one_of(name: &str, names: &[&str]) -> bool {
+    names
+        .iter()
+        .any(|candidate| candidate.eq_ignore_ascii_case(name))
+}
+
+/// Whether a name can be written as an attribute name as is. Names that come from data can't
+/// contain quotes, `=`, `>` or whitespace, which would end the name or the tag.
+fn is_valid_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace()
+                || c.is_control()
+                || matches!(c, '"' | '\'' | '<' | '>' | '/' | '=' | '&')
+                || matches!(c, '\u{FDD0}'..='\u{FDEF}')
+                || (c as u32) & 0xFFFE == 0xFFFE
+        })
+}
+
+/// Adds attributes to a [`SimpleElement`](struct.SimpleElement.html)'s attributes, in order.
+/// An attribute replaces any previous one with the same name, ignoring ASCII case.
+///
+/// This is what spreading attributes inside `rsx!` uses, so the attribute that comes last
+/// wins. Attributes with names that can't be rendered safely, like `a"b`, are dropped when
+/// rendering.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// let aria = vec![("aria-label", "Close"), ("ID", "ignored")];
+///
+/// let rendered = html! { <button {..aria} id={"close"} /> };
+///
+/// assert!(rendered.contains(r#" aria-label="Close""#));
+/// assert!(rendered.contains(r#" id="close""#));
+/// assert!(!rendered.contains("ignored"));
+/// ```
+pub fn spread_attributes<'a, K, V>(
+    attributes: &mut HashMap<Cow<'a, str>, AttributeValue<'a>>,
+    spread: impl IntoIterator<Item = (K, V)>,
+) where
+    K: Into<Cow<'a, str>>,
+    V: Into<AttributeValue<'a>>,
+{
+    for (name, value) in spread {
+        let name = name.into();
+        attributes.retain(|existing, _| !existing.eq_ignore_ascii_case(&name));
+        attributes.insert(name, value.into());
+    }
+}
+
+/// Mirrored by the `rsx!` macro, which requires a `TrustedScript` for these at compile time
+fn is_event_handler(name: &str) -> bool {
+    EVENT_HANDLER_PREFIXES.iter().any(|prefix| {
+        name.len() > prefix.len()
+            && name
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    })
+}
+
+/// `onclick`, and the event handlers of Alpine.js, Vue and htmx, like `@click`, `x-on:click`,
+/// `v-on:click` and `hx-on:click`
+const EVENT_HANDLER_PREFIXES: &[&str] = &["on", "@", "x-on:", "v-on:", "hx-on:", "hx-on-"];
+
+pub(crate) fn write_attributes<'a, W: Write>(
+    maybe_attributes: Attributes<'a>,
+    writer: &mut W,
+) -> Result {
     match maybe_attributes {
         None => Ok(()),
         Some(mut attributes) => {
             for (key, value) in attributes.drain() {
-                write!(writer, " {}=\"", key)?;
-                escape_html(&value, writer)?;
-                write!(writer, "\"")?;
+                write_attribute(&key, value, writer)?;
             }
             Ok(())
         }
     }
 }
 
+/// Writes a single attribute, escaping its value for the attribute it is in
+pub(crate) fn write_attribute<W: Write>(
+    key: &str,
+    value: AttributeValue,
+    writer: &mut W,
+) -> Result {
+    if !is_valid_attribute_name(key)
+        || is_event_handler(key) && !matches!(value, AttributeValue::TrustedScript(_))
+    {
+        return Ok(());
+    }
+
+    write!(writer, " {}=\"", key)?;
+    match value {
+        AttributeValue::TrustedUrl(url) if is_one_of(key, URL_ATTRIBUTES) => {
+            escape_url(&url, writer)?
+        }
+        AttributeValue::Interpolated(write) if is_one_of(key, URL_ATTRIBUTES) => {
+            let mut url = SanitizeUrl::new(writer);
+            write(&mut url)?;
+            url.finish()?
+        }
+        AttributeValue::Interpolated(write) => write(&mut EscapeAttribute(writer))?,
+        AttributeValue::Text(value)
+        | AttributeValue::TrustedScript(value)
+        | AttributeValue::TrustedUrl(value) => {
+            if is_one_of(key, URL_ATTRIBUTES) {
+                escape_url(sanitize_url(&value), writer)?
+            } else {
+                escape_attribute(&value, writer)?
+            }
+        }
+    }
+    write!(writer, "\"")
+}
+
+/// Escapes everything written into it as an attribute value
+struct EscapeAttribute<'w, W: Write>(&'w mut W);
+
+impl<W: Write> Write for EscapeAttribute<'_, W> {
+    fn write_str(&mut self, value: &str) -> Result {
+        escape_attribute(value, self.0)
+    }
+}
+
 impl<T: Render> Render for SimpleElement<'_, T> {
     fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        let raw_text = raw_text_element(self.tag_name);
+        #[allow(unused_mut)]
+        let mut attributes = self.attributes;
+        #[cfg(feature = "csp")]
+        let hash_contents = raw_text.is_some() && crate::csp::stamp(&mut attributes);
+
         match self.contents {
             None => {
                 write!(writer, "<{}", self.tag_name)?;
-                write_attributes(self.attributes, writer)?;
+                write_attributes(attributes, writer)?;
                 write!(writer, "/>")
             }
             Some(renderable) => {
                 write!(writer, "<{}", self.tag_name)?;
-                write_attributes(self.attributes, writer)?;
+                write_attributes(attributes, writer)?;
                 write!(writer, ">")?;
-                renderable.render_into(writer)?;
+                #[cfg(feature = "csp")]
+                if let (true, Some(raw_text)) = (hash_contents, raw_text) {
+                    let mut contents = String::new();
+                    with_raw_text(Some(raw_text), || renderable.render_into(&mut contents))?;
+                    crate::csp::record_hash(raw_text, &contents);
+                    writer.write_str(&contents)?;
+                    return write!(writer, "</{}>", self.tag_name);
+                }
+                with_raw_text(raw_text, || renderable.render_into(writer))?;
                 write!(writer, "</{}>", self.tag_name)
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrustedScript;
+    use pretty_assertions::assert_eq;
+
+    fn element<'a>(
+        tag_name: &'a str,
+        attributes: Vec<(&'a str, AttributeValue<'a>)>,
+    ) -> SimpleElement<'a, ()> {
+        SimpleElement {
+            tag_name,
+            attributes: Some(
+                attributes
+                    .into_iter()
+                    .map(|(name, value)| (name.into(), value))
+                    .collect(),
+            ),
+            contents: None,
+        }
+    }
+
+    #[test]
+    fn percent_encodes_url_attributes() {
+        let rendered = element("a", vec![("href", "/users/Gal Schlezinger".into())]).render();
+        assert_eq!(rendered, r#"<a href="/users/Gal%20Schlezinger"/>"#);
+    }
+
+    #[test]
+    fn blocks_unsafe_url_schemes() {
+        let rendered = element("a", vec![("href", "\tjavascript:alert(1)".into())]).render();
+        assert_eq!(rendered, r#"<a href="about:invalid"/>"#);
+
+        let rendered = element("img", vec![("SRC", "data:image/png,x".into())]).render();
+        assert_eq!(rendered, r#"<img SRC="about:invalid"/>"#);
+
+        let rendered = element("a", vec![("href", "mailto:me@example.com".into())]).render();
+        assert_eq!(rendered, r#"<a href="mailto:me@example.com"/>"#);
+    }
+
+    #[test]
+    fn drops_untrusted_event_handlers() {
+        let rendered = element("img", vec![("ONERROR", "alert(1)".into())]).render();
+        assert_eq!(rendered, "<img/>");
+
+        for name in [
+            "@click",
+            "X-On:click.away",
+            "v-on:load",
+            "hx-on::after-request",
+            "hx-on-click",
+        ] {
+            let rendered = element("img", vec![(name, "alert(1)".into())]).render();
+            assert_eq!(rendered, "<img/>");
+        }
+
+        let trusted = TrustedScript::new("track(\"img\")");
+        let rendered = element("img", vec![("onload", trusted.into())]).render();
+        assert_eq!(rendered, r#"<img onload="track(&quot;img&quot;)"/>"#);
+    }
+
+    #[test]
+    fn escapes_raw_text_only_inside_raw_text_elements() {
+        let style = SimpleElement {
+            tag_name: "style",
+            attributes: None,
+            contents: Some((
+                "a > b { content: \"</style>\" }",
+                SimpleElement {
+                    tag_name: "b",
+                    attributes: None,
+                    contents: Some("</b>"),
+                },
+            )),
+        };
+
+        assert_eq!(
+            style.render(),
+            r#"<style>a > b { content: "<\/style>" }<b>&lt;/b&gt;</b></style>"#
+        );
+    }
+
+    #[test]
+    fn escapes_non_ascii_raw_text_in_its_own_language() {
+        use crate::html_escaping::{with_escape_policy, EscapePolicy};
+
+        let raw_text = |tag_name| SimpleElement {
+            tag_name,
+            attributes: None,
+            contents: Some("é😀"),
+        };
+
+        let (script, style) = with_escape_policy(EscapePolicy::full().ascii_only(), || {
+            (raw_text("script").render(), raw_text("style").render())
+        });
+        assert_eq!(script, r"<script>\u00E9\uD83D\uDE00</script>");
+        assert_eq!(style, r"<style>\E9 \1F600 </style>");
+    }
+
+    #[test]
+    fn escapes_minimal_attributes() {
+        use crate::html_escaping::{with_escape_policy, EscapePolicy};
+
+        let rendered = with_escape_policy(EscapePolicy::minimal(), || {
+            element("div", vec![("title", "<a> & \"b\" 'c'".into())]).render()
+        });
+        assert_eq!(rendered, r#"<div title="<a> &amp; &quot;b&quot; 'c'"/>"#);
+    }
+
+    #[test]
+    fn strips_invalid_characters_without_closing_raw_text() {
+        use crate::html_escaping::{with_escape_policy, EscapePolicy, InvalidCharacters};
+
+        let policy = EscapePolicy::full().invalid_characters(InvalidCharacters::Strip);
+        let rendered = with_escape_policy(policy, || {
+            SimpleElement {
+                tag_name: "script",
+                attributes: None,
+                contents: Some("<\0/script>"),
+            }
+            .render()
+        });
+        assert_eq!(rendered, r"<script><\/script></script>");
+
+        let rendered = with_escape_policy(policy, || {
+            element("div", vec![("title", "a\u{7F}b".into())]).render()
+        });
+        assert_eq!(rendered, r#"<div title="ab"/>"#);
+    }
+
+    #[test]
+    fn drops_attribute_names_that_break_the_tag() {
+        let rendered = element("div", vec![("x\"><script>", "".into())]).render();
+        assert_eq!(rendered, "<div/>");
+
+        let rendered = element("div", vec![("a b", "".into())]).render();
+        assert_eq!(rendered, "<div/>");
+
+        let rendered = element("div", vec![("x-transition:enter.scale", "90".into())]).render();
+        assert_eq!(rendered, r#"<div x-transition:enter.scale="90"/>"#);
+    }
+
+    #[test]
+    fn spreads_attributes_in_order() {
+        let mut attributes = HashMap::new();
+        spread_attributes(&mut attributes, vec![("ID", "first"), ("title", "kept")]);
+        spread_attributes(&mut attributes, Some(("id", String::from("second"))));
+
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes["id"].as_str(), Some("second"));
+        assert_eq!(attributes["title"].as_str(), Some("kept"));
+    }
+
+    #[test]
+    fn escapes_and_sanitizes_interpolated_values() {
+        let render = |tag_name, key, parts: &[&str]| {
+            let write =
+                |writer: &mut dyn Write| parts.iter().try_for_each(|part| writer.write_str(part));
+            element(tag_name, vec![(key, AttributeValue::interpolated(&write))]).render()
+        };
+
+        let rendered = render("div", "title", &["<a>", ":\"x\""]);
+        assert_eq!(rendered, r#"<div title="&lt;a&gt;:&quot;x&quot;"/>"#);
+
+        let rendered = render("a", "href", &["java", "script", ":alert(1)"]);
+        assert_eq!(rendered, r#"<a href="about:invalid"/>"#);
+
+        let rendered = render("a", "href", &[" \t", "HTTPS", "://a.com/", "x y ", " "]);
+        assert_eq!(rendered, r#"<a href="HTTPS://a.com/x%20y"/>"#);
+
+        let rendered = render("a", "href", &["/search?q=", "a&b", "\n"]);
+        assert_eq!(rendered, r#"<a href="/search?q=a&amp;b"/>"#);
+    }
+}
@@ -0,0 +1,511 @@
+//! Typed inline styles
+
+use std::borrow::Cow;
+use std::fmt::{self, Formatter, Write};
+
+/// An inline `style` attribute value, built from typed CSS properties.
+///
+/// Every value is either typed or validated, so a declaration can't break out into another
+/// declaration, even when it comes from user input:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// use render::style::{px, Color, FontFamily, Style, TextAlign};
+///
+/// let user_color = Color::parse("#1e90ff; position: fixed").unwrap_or(Color::rgb(0, 0, 0));
+///
+/// let style = Style::new()
+///     .color(user_color)
+///     .font_family(FontFamily::new().family("Open Sans").sans_serif())
+///     .padding(px(12))
+///     .text_align(TextAlign::Center);
+///
+/// let rendered = html! { <td style={style} /> };
+///
+/// assert_eq!(
+///     rendered,
+///     r#"<td style="color:#000000;font-family:&quot;Open Sans&quot;,sans-serif;padding:12px;text-align:center"/>"#
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Style {
+    declarations: Vec<(Cow<'static, str>, String)>,
+}
+
+macro_rules! style_properties {
+    ($($(#[$attr:meta])* $method:ident($type:ty) => $name:expr;)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $method(self, value: $type) -> Self {
+                self.declare($name, value.to_string())
+            }
+        )*
+    };
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    style_properties! {
+        color(Color) => "color";
+        background_color(Color) => "background-color";
+        font_family(FontFamily) => "font-family";
+        font_size(Length) => "font-size";
+        font_weight(FontWeight) => "font-weight";
+        font_style(FontStyle) => "font-style";
+        text_align(TextAlign) => "text-align";
+        text_decoration(TextDecoration) => "text-decoration";
+        vertical_align(VerticalAlign) => "vertical-align";
+        display(Display) => "display";
+        width(Length) => "width";
+        height(Length) => "height";
+        max_width(Length) => "max-width";
+        min_width(Length) => "min-width";
+        margin(Length) => "margin";
+        margin_top(Length) => "margin-top";
+        margin_right(Length) => "margin-right";
+        margin_bottom(Length) => "margin-bottom";
+        margin_left(Length) => "margin-left";
+        padding(Length) => "padding";
+        padding_top(Length) => "padding-top";
+        padding_right(Length) => "padding-right";
+        padding_bottom(Length) => "padding-bottom";
+        padding_left(Length) => "padding-left";
+        border_radius(Length) => "border-radius";
+        border_collapse(BorderCollapse) => "border-collapse";
+    }
+
+    /// Sets a line height relative to the font size, like `1.5`
+    pub fn line_height(self, value: f64) -> Self {
+        self.declare("line-height", finite(value).to_string())
+    }
+
+    /// Sets the `border` shorthand property
+    pub fn border(self, width: Length, style: BorderStyle, color: Color) -> Self {
+        self.declare("border", format!("{} {} {}", width, style, color))
+    }
+
+    /// Sets a property that has no typed setter.
+    ///
+    /// The name must be a valid CSS property name, and the value can't contain anything that
+    /// could end the declaration or the attribute, like `;`, quotes, braces or comments.
+    ///
+    /// ```rust
+    /// # use pretty_assertions::assert_eq;
+    /// # use render::style::Style;
+    /// let style = Style::new().property("mso-line-height-rule", "exactly").unwrap();
+    /// assert_eq!(style.to_string(), "mso-line-height-rule:exactly");
+    ///
+    /// assert!(Style::new().property("color", "red; position: fixed").is_err());
+    /// ```
+    pub fn property(self, name: &str, value: &str) -> Result<Self, InvalidStyle> {
+        if !is_valid_property_name(name) || !is_safe_value(value) {
+            return Err(InvalidStyle {
+                property: name.to_owned(),
+            });
+        }
+
+        Ok(self.declare(Cow::Owned(name.to_owned()), value.trim().to_owned()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.declarations.is_empty()
+    }
+
+    fn declare(mut self, name: impl Into<Cow<'static, str>>, value: String) -> Self {
+        let name = name.into();
+        match self.declarations.iter_mut().find(|(key, _)| *key == name) {
+            Some(declaration) => declaration.1 = value,
+            None => self.declarations.push((name, value)),
+        }
+        self
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.declarations.iter().enumerate() {
+            if index > 0 {
+                f.write_char(';')?;
+            }
+            write!(f, "{}:{}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Style> for Cow<'_, str> {
+    fn from(style: Style) -> Self {
+        Cow::Owned(style.to_string())
+    }
+}
+
+/// The error returned when an untyped property would be unsafe to render
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidStyle {
+    pub property: String,
+}
+
+impl fmt::Display for InvalidStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value for the `{}` CSS property", self.property)
+    }
+}
+
+impl std::error::Error for InvalidStyle {}
+
+fn is_valid_property_name(name: &str) -> bool {
+    let body = name.trim_start_matches('-');
+    !body.is_empty()
+        && name.len() - body.len() <= 2
+        && body.starts_with(|c: char| c.is_ascii_alphabetic())
+        && body.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn is_safe_value(value: &str) -> bool {
+    let lowercase = value.to_ascii_lowercase();
+    !value.trim().is_empty()
+        && !value.contains(|c: char| {
+            c.is_control() || matches!(c, ';' | '{' | '}' | '<' | '>' | '"' | '\'' | '\\')
+        })
+        && !lowercase.contains("/*")
+        && !lowercase.contains("url(")
+        && !lowercase.contains("expression(")
+}
+
+/// A CSS length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Zero,
+    Auto,
+    Px(f64),
+    Em(f64),
+    Rem(f64),
+    Percent(f64),
+}
+
+pub fn px(value: impl Into<f64>) -> Length {
+    Length::Px(value.into())
+}
+
+pub fn em(value: impl Into<f64>) -> Length {
+    Length::Em(value.into())
+}
+
+pub fn rem(value: impl Into<f64>) -> Length {
+    Length::Rem(value.into())
+}
+
+pub fn percent(value: impl Into<f64>) -> Length {
+    Length::Percent(value.into())
+}
+
+fn finite(value: f64) -> f64 {
+    if value.is_finite() {
+        value
+    } else {
+        0.0
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Zero => f.write_str("0"),
+            Self::Auto => f.write_str("auto"),
+            Self::Px(value) => write!(f, "{}px", finite(value)),
+            Self::Em(value) => write!(f, "{}em", finite(value)),
+            Self::Rem(value) => write!(f, "{}rem", finite(value)),
+            Self::Percent(value) => write!(f, "{}%", finite(value)),
+        }
+    }
+}
+
+/// A CSS color
+#[derive(Debug, Clone, PartialEq)]
+pub enum Color {
+    Rgb(u8, u8, u8),
+    Rgba(u8, u8, u8, f64),
+    Named(String),
+    Transparent,
+    CurrentColor,
+}
+
+impl Color {
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self::Rgb(red, green, blue)
+    }
+
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: f64) -> Self {
+        Self::Rgba(red, green, blue, alpha)
+    }
+
+    /// Parses an untrusted color, like `#fff`, `#1e90ff` or `rebeccapurple`.
+    /// Returns `None` for anything else.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+            return match hex.len() {
+                3 => {
+                    let mut channels = hex.chars().map(|c| channel(&format!("{}{}", c, c)));
+                    Some(Self::Rgb(
+                        channels.next()??,
+                        channels.next()??,
+                        channels.next()??,
+                    ))
+                }
+                6 => Some(Self::Rgb(
+                    channel(&hex[0..2])?,
+                    channel(&hex[2..4])?,
+                    channel(&hex[4..6])?,
+                )),
+                _ => None,
+            };
+        }
+
+        match value.to_ascii_lowercase().as_str() {
+            "transparent" => Some(Self::Transparent),
+            "currentcolor" => Some(Self::CurrentColor),
+            name if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic()) => {
+                Some(Self::Named(name.to_owned()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rgb(red, green, blue) => write!(f, "#{:02x}{:02x}{:02x}", red, green, blue),
+            Self::Rgba(red, green, blue, alpha) => write!(
+                f,
+                "rgba({},{},{},{})",
+                red,
+                green,
+                blue,
+                finite(*alpha).clamp(0.0, 1.0)
+            ),
+            Self::Named(name) if name.chars().all(|c| c.is_ascii_alphabetic()) => f.write_str(name),
+            Self::Named(_) => f.write_str("currentcolor"),
+            Self::Transparent => f.write_str("transparent"),
+            Self::CurrentColor => f.write_str("currentcolor"),
+        }
+    }
+}
+
+/// A `font-family` list. Family names are quoted and escaped, generic families are not.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FontFamily {
+    families: Vec<String>,
+}
+
+impl FontFamily {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn family(mut self, name: &str) -> Self {
+        let mut quoted = String::with_capacity(name.len() + 2);
+        quoted.push('"');
+        for c in name.chars() {
+            match c {
+                '"' | '\\' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                c if c.is_control() => {
+                    write!(quoted, "\\{:x} ", c as u32).unwrap();
+                }
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        self.families.push(quoted);
+        self
+    }
+
+    pub fn serif(self) -> Self {
+        self.generic("serif")
+    }
+
+    pub fn sans_serif(self) -> Self {
+        self.generic("sans-serif")
+    }
+
+    pub fn monospace(self) -> Self {
+        self.generic("monospace")
+    }
+
+    fn generic(mut self, name: &str) -> Self {
+        self.families.push(name.to_owned());
+        self
+    }
+}
+
+impl fmt::Display for FontFamily {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.families.join(","))
+    }
+}
+
+/// A `font-weight` value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontWeight {
+    Normal,
+    Bold,
+    /// A numeric weight, clamped between 1 and 1000
+    Weight(u16),
+}
+
+impl fmt::Display for FontWeight {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => f.write_str("normal"),
+            Self::Bold => f.write_str("bold"),
+            Self::Weight(weight) => write!(f, "{}", (*weight).clamp(1, 1000)),
+        }
+    }
+}
+
+macro_rules! keyword_enum {
+    ($(#[$attr:meta])* $name:ident { $($variant:ident => $keyword:expr),* $(,)? }) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str(match self {
+                    $(Self::$variant => $keyword),*
+                })
+            }
+        }
+    };
+}
+
+keyword_enum!(
+    /// A `font-style` value
+    FontStyle {
+        Normal => "normal",
+        Italic => "italic",
+    }
+);
+
+keyword_enum!(
+    /// A `text-align` value
+    TextAlign {
+        Left => "left",
+        Right => "right",
+        Center => "center",
+        Justify => "justify",
+    }
+);
+
+keyword_enum!(
+    /// A `text-decoration` value
+    TextDecoration {
+        None => "none",
+        Underline => "underline",
+        LineThrough => "line-through",
+    }
+);
+
+keyword_enum!(
+    /// A `vertical-align` value
+    VerticalAlign {
+        Top => "top",
+        Middle => "middle",
+        Bottom => "bottom",
+        Baseline => "baseline",
+    }
+);
+
+keyword_enum!(
+    /// A `display` value
+    Display {
+        None => "none",
+        Block => "block",
+        Inline => "inline",
+        InlineBlock => "inline-block",
+        Flex => "flex",
+        Grid => "grid",
+        Table => "table",
+        TableCell => "table-cell",
+    }
+);
+
+keyword_enum!(
+    /// A `border-style` value
+    BorderStyle {
+        None => "none",
+        Solid => "solid",
+        Dashed => "dashed",
+        Dotted => "dotted",
+    }
+);
+
+keyword_enum!(
+    /// A `border-collapse` value
+    BorderCollapse {
+        Collapse => "collapse",
+        Separate => "separate",
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_untrusted_colors() {
+        assert_eq!(Color::parse("#FFF"), Some(Color::rgb(255, 255, 255)));
+        assert_eq!(Color::parse(" #1e90ff "), Some(Color::rgb(30, 144, 255)));
+        assert_eq!(Color::parse("Red"), Some(Color::Named("red".to_owned())));
+        assert_eq!(Color::parse("red;display:none"), None);
+        assert_eq!(Color::parse("#12345"), None);
+        assert_eq!(Color::parse(""), None);
+    }
+
+    #[test]
+    fn escapes_font_family_names() {
+        let family = FontFamily::new().family(r#"Evil"; color: red"#).serif();
+        assert_eq!(family.to_string(), r#""Evil\"; color: red",serif"#);
+    }
+
+    #[test]
+    fn replaces_repeated_properties() {
+        let style = Style::new()
+            .margin(Length::Zero)
+            .width(percent(100))
+            .margin(px(4));
+        assert_eq!(style.to_string(), "margin:4px;width:100%");
+    }
+
+    #[test]
+    fn replaces_non_finite_numbers() {
+        let style = Style::new().line_height(f64::NAN).width(px(f64::INFINITY));
+        assert_eq!(style.to_string(), "line-height:0;width:0px");
+    }
+
+    #[test]
+    fn rejects_unsafe_custom_properties() {
+        assert!(Style::new().property("--brand", "#fff").is_ok());
+        assert!(Style::new().property("color:red;x", "red").is_err());
+        assert!(Style::new()
+            .property("background", "url(javascript:x)")
+            .is_err());
+        assert!(Style::new().property("width", "1px /* */").is_err());
+    }
+}
@@ -1,22 +1,22 @@
-use crate::html_escaping::escape_html;
+use crate::html_escaping::{escape_text, with_isolated_direction};
 use crate::Render;
 use std::fmt::{Result, Write};
 
 impl Render for String {
     fn render_into<W: Write>(self, writer: &mut W) -> Result {
-        escape_html(&self, writer)
+        escape_text(&self, writer)
     }
 }
 
 impl Render for &str {
     fn render_into<W: Write>(self, writer: &mut W) -> Result {
-        escape_html(self, writer)
+        escape_text(self, writer)
     }
 }
 
 impl Render for std::borrow::Cow<'_, str> {
     fn render_into<W: Write>(self, writer: &mut W) -> Result {
-        escape_html(&self, writer)
+        escape_text(&self, writer)
     }
 }
 
@@ -37,6 +37,43 @@ impl<'s> Render for Raw<'s> {
     }
 }
 
+/// Isolates its contents from the surrounding text direction, using the Unicode
+/// FIRST STRONG ISOLATE and POP DIRECTIONAL ISOLATE characters.
+///
+/// Use it for user-generated strings, so a right-to-left name can't reorder the text around it.
+/// Bidi embedding, override and isolate controls in the text inside are removed, so it can't
+/// close the isolate early or override the direction of the text around it:
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render::html;
+/// use render::Isolate;
+///
+/// let user_name = "\u{202E}nimda";
+/// assert_eq!(
+///     html! { <p>{Isolate(user_name)}{" commented"}</p> },
+///     "<p>\u{2068}nimda\u{2069} commented</p>"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Isolate<T: Render>(pub T);
+
+impl<T: Render> Render for Isolate<T> {
+    fn render_into<W: Write>(self, writer: &mut W) -> Result {
+        escape_text("\u{2068}", writer)?;
+        with_isolated_direction(|| self.0.render_into(writer))?;
+        escape_text("\u{2069}", writer)
+    }
+}
+
+/// Creates a raw (unencoded) html string
+#[macro_export]
+macro_rules! raw {
+    ($text:expr) => {
+        $crate::Raw::from($text)
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,12 +91,45 @@ mod tests {
         let rendered = Raw::from("<Hello />").render();
         assert_eq!(rendered, "<Hello />");
     }
-}
 
-/// Creates a raw (unencoded) html string
-#[macro_export]
-macro_rules! raw {
-    ($text:expr) => {
-        ::render::Raw::from($text)
-    };
+    #[test]
+    fn follows_the_escape_policy() {
+        use crate::html_escaping::{with_escape_policy, EscapePolicy};
+        use pretty_assertions::assert_eq;
+
+        let minimal = with_escape_policy(EscapePolicy::minimal(), || {
+            (String::from("a < b > c"), " & \"d\" 'e'").render()
+        });
+        assert_eq!(minimal, "a &lt; b > c &amp; \"d\" 'e'");
+
+        let ascii = with_escape_policy(EscapePolicy::minimal().ascii_only(), || {
+            std::borrow::Cow::from("naïve 😀").render()
+        });
+        assert_eq!(ascii, "na&#xEF;ve &#x1F600;");
+    }
+
+    #[test]
+    fn handles_invalid_characters() {
+        use crate::html_escaping::{with_escape_policy, EscapePolicy, InvalidCharacters};
+        use pretty_assertions::assert_eq;
+
+        let policy = EscapePolicy::full()
+            .ascii_only()
+            .invalid_characters(InvalidCharacters::Replace);
+        let rendered = with_escape_policy(policy, || "a\0b\u{1}\tc\u{FDD0}\u{10FFFE}".render());
+        assert_eq!(rendered, "a&#xFFFD;b&#xFFFD;\tc&#xFFFD;&#xFFFD;");
+    }
+    #[test]
+    fn keeps_isolated_text_inside_its_isolate() {
+        use crate::html_escaping::{with_escape_policy, EscapePolicy};
+        use pretty_assertions::assert_eq;
+
+        let rendered = (Isolate("\u{2069}\u{202E}evil"), " ok").render();
+        assert_eq!(rendered, "\u{2068}evil\u{2069} ok");
+
+        let rendered = with_escape_policy(EscapePolicy::full().ascii_only(), || {
+            Isolate(String::from("שלום\u{2066}\u{202A}")).render()
+        });
+        assert_eq!(rendered, "&#x2068;&#x5E9;&#x5DC;&#x5D5;&#x5DD;&#x2069;");
+    }
 }
@@ -1,10 +1,47 @@
+use crate::declaration::Declaration;
 use crate::element::Element;
+use crate::for_loop::ForLoop;
+use crate::if_else::IfElse;
+use crate::let_binding::Let;
+use crate::match_arms::Match;
+use crate::text::Text;
 use quote::{quote, ToTokens};
 use syn::parse::{Parse, ParseStream, Result};
 
 pub enum Child {
     Element(Element),
     RawBlock(syn::Block),
+    Text(Text),
+    IfElse(IfElse),
+    ForLoop(Box<ForLoop>),
+    Match(Box<Match>),
+    Let(Box<Let>),
+    Declaration(Declaration),
+}
+
+impl Child {
+    /// Whether a keyword starts control flow, like `if x { ... }`, rather than being a word
+    /// of text, like in `Do it for me` or `<p>let it be</p>`
+    pub fn starts_control_flow(input: ParseStream) -> bool {
+        let fork = input.fork();
+        if fork.peek(syn::Token![let]) {
+            return matches!(fork.parse::<syn::Stmt>(), Ok(syn::Stmt::Local(_)));
+        }
+
+        let has_head = if fork.peek(syn::Token![for]) {
+            fork.parse::<syn::Token![for]>().is_ok()
+                && fork.parse::<syn::Pat>().is_ok()
+                && fork.parse::<syn::Token![in]>().is_ok()
+        } else if fork.peek(syn::Token![if]) || fork.peek(syn::Token![match]) {
+            fork.parse::<proc_macro2::TokenTree>().is_ok()
+        } else {
+            false
+        };
+
+        has_head
+            && fork.call(syn::Expr::parse_without_eager_brace).is_ok()
+            && fork.peek(syn::token::Brace)
+    }
 }
 
 impl ToTokens for Child {
@@ -20,18 +57,42 @@ impl ToTokens for Child {
                 };
                 ts.to_tokens(tokens);
             }
+            Self::Text(text) => text.to_tokens(tokens),
+            Self::IfElse(if_else) => if_else.to_tokens(tokens),
+            Self::ForLoop(for_loop) => for_loop.to_tokens(tokens),
+            Self::Match(match_arms) => match_arms.to_tokens(tokens),
+            Self::Let(binding) => binding.to_tokens(tokens),
+            Self::Declaration(declaration) => declaration.to_tokens(tokens),
         }
     }
 }
 
 impl Parse for Child {
     fn parse(input: ParseStream) -> Result<Self> {
-        match input.parse::<Element>() {
-            Ok(element) => Ok(Self::Element(element)),
-            Err(_) => {
-                let block = input.parse::<syn::Block>()?;
-                Ok(Self::RawBlock(block))
-            }
+        if input.peek(syn::Token![if]) {
+            return Ok(Self::IfElse(input.parse()?));
+        }
+
+        if input.peek(syn::Token![for]) {
+            return Ok(Self::ForLoop(Box::new(input.parse()?)));
         }
+
+        if input.peek(syn::Token![match]) {
+            return Ok(Self::Match(Box::new(input.parse()?)));
+        }
+
+        if input.peek(syn::Token![let]) {
+            return Ok(Self::Let(Box::new(input.parse()?)));
+        }
+
+        if Declaration::is_start(input) {
+            return Ok(Self::Declaration(input.parse()?));
+        }
+
+        if input.peek(syn::Token![<]) {
+            return Ok(Self::Element(input.parse()?));
+        }
+
+        Ok(Self::RawBlock(input.parse()?))
     }
 }
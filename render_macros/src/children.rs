@@ -1,4 +1,6 @@
 use crate::child::Child;
+use crate::crate_path::crate_path;
+use crate::text::Text;
 use quote::{quote, ToTokens};
 use syn::parse::{Parse, ParseStream, Result};
 
@@ -16,6 +18,18 @@ impl Children {
         self.nodes.len()
     }
 
+    /// The children as a single renderable: `()`, the only child, or nested tuples
+    pub fn as_renderable_tokens(&self) -> proc_macro2::TokenStream {
+        match self.nodes.as_slice() {
+            [] => quote!(()),
+            [child] => quote!(#child),
+            [first, second, rest @ ..] => rest.iter().fold(
+                quote!((#first, #second)),
+                |renderable, current| quote!((#renderable, #current)),
+            ),
+        }
+    }
+
     pub fn as_option_of_tuples_tokens(&self) -> proc_macro2::TokenStream {
         let children_quotes: Vec<_> = self
             .nodes
@@ -45,12 +59,17 @@ impl Children {
     }
 }
 
+/// Parses children until a closing tag or the end of the input
 impl Parse for Children {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut nodes = vec![];
+        let mut nodes: Vec<Child> = vec![];
 
-        while !input.peek(syn::Token![<]) || !input.peek2(syn::Token![/]) {
-            let child = input.parse::<Child>()?;
+        while !input.is_empty() && !is_closing_tag(input) {
+            let child = if Text::is_start(input) && !Child::starts_control_flow(input) {
+                Child::Text(Text::parse_after(input, !nodes.is_empty())?)
+            } else {
+                input.parse::<Child>()?
+            };
             nodes.push(child);
         }
 
@@ -58,6 +77,46 @@ impl Parse for Children {
     }
 }
 
+impl Children {
+    /// Parses the nodes at the top level of `rsx!`, which are rendered in order
+    pub fn parse_root(input: ParseStream) -> Result<Self> {
+        let children = input.parse::<Self>()?;
+
+        if !input.is_empty() {
+            return Err(input.error("Unexpected closing tag, without a matching opening tag"));
+        }
+
+        for child in children.nodes.iter() {
+            if let Child::Text(text) = child {
+                if matches!(text.value().trim(), "," | ";") {
+                    return Err(syn::Error::new(
+                        text.span(),
+                        "Root nodes aren't separated by commas or semicolons. Use {\",\"} to render one",
+                    ));
+                }
+            }
+        }
+
+        Ok(children)
+    }
+
+    /// The top level of `rsx!`, wrapped in a fragment unless it is a single node
+    pub fn as_root_tokens(&self) -> proc_macro2::TokenStream {
+        match self.nodes.as_slice() {
+            [child] => quote!(#child),
+            _ => {
+                let renderable = self.as_renderable_tokens();
+                let render = crate_path();
+                quote!(#render::Fragment { children: #renderable })
+            }
+        }
+    }
+}
+
+pub fn is_closing_tag(input: ParseStream) -> bool {
+    input.peek(syn::Token![<]) && input.peek2(syn::Token![/])
+}
+
 impl ToTokens for Children {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         self.as_option_of_tuples_tokens().to_tokens(tokens);
@@ -0,0 +1,54 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use std::cell::RefCell;
+use syn::parse::{ParseStream, Result};
+
+thread_local! {
+    /// The path set for the macro invocation being expanded
+    static CRATE_PATH: RefCell<Option<syn::Path>> = const { RefCell::new(None) };
+}
+
+/// The path to the `render` crate in generated code: `::render`, unless the macro
+/// invocation overrides it with `crate = "..."`
+pub fn crate_path() -> TokenStream {
+    CRATE_PATH.with(|path| match &*path.borrow() {
+        Some(path) => path.to_token_stream(),
+        None => quote!(::render),
+    })
+}
+
+/// Runs `f` with `path` as the crate path, restoring the previous one afterwards, even when
+/// `f` aborts
+pub fn with_crate_path<T>(path: Option<syn::Path>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<syn::Path>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            CRATE_PATH.with(|path| *path.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(CRATE_PATH.with(|current| current.replace(path)));
+    f()
+}
+
+/// Parses an optional `crate = "path"` or `crate = path` override
+pub fn parse_override(input: ParseStream) -> Result<Option<syn::Path>> {
+    if !(input.peek(syn::Token![crate]) && input.peek2(syn::Token![=])) {
+        return Ok(None);
+    }
+
+    input.parse::<syn::Token![crate]>()?;
+    input.parse::<syn::Token![=]>()?;
+
+    let path = if input.peek(syn::LitStr) {
+        input
+            .parse::<syn::LitStr>()?
+            .parse_with(syn::Path::parse_mod_style)?
+    } else {
+        input.call(syn::Path::parse_mod_style)?
+    };
+
+    Ok(Some(path))
+}
@@ -0,0 +1,78 @@
+use crate::crate_path::crate_path;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream, Result};
+
+/// A child starting with `<!`: a comment, like `<!-- "text" -->` or `<!-- {value} -->`,
+/// or the `<!DOCTYPE html>` declaration
+pub enum Declaration {
+    Comment(TokenStream),
+    Doctype,
+}
+
+impl Declaration {
+    pub fn is_start(input: ParseStream) -> bool {
+        input.peek(syn::Token![<]) && input.peek2(syn::Token![!])
+    }
+}
+
+impl Parse for Declaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<syn::Token![<]>()?;
+        let bang = input.parse::<syn::Token![!]>()?;
+
+        if input.peek(syn::Token![-]) {
+            input.parse::<syn::Token![-]>()?;
+            input.parse::<syn::Token![-]>()?;
+
+            let text = if input.peek(syn::LitStr) {
+                input.parse::<syn::LitStr>()?.into_token_stream()
+            } else if input.peek(syn::token::Brace) {
+                let block = input.parse::<syn::Block>()?;
+                match block.stmts.as_slice() {
+                    [statement] => statement.into_token_stream(),
+                    _ => block.into_token_stream(),
+                }
+            } else {
+                return Err(input.error("Expected a string literal or a block inside the comment"));
+            };
+
+            if !(input.peek(syn::Token![-]) && input.peek2(syn::Token![->])) {
+                return Err(input.error("Expected `-->` to close the comment"));
+            }
+            input.parse::<syn::Token![-]>()?;
+            input.parse::<syn::Token![->]>()?;
+
+            return Ok(Self::Comment(text));
+        }
+
+        let doctype = input.parse::<syn::Ident>()?;
+        if !doctype.to_string().eq_ignore_ascii_case("doctype") {
+            return Err(syn::Error::new(
+                bang.span,
+                "Expected a comment, like `<!-- \"text\" -->`, or `<!DOCTYPE html>`",
+            ));
+        }
+        let name = input.parse::<syn::Ident>()?;
+        if !name.to_string().eq_ignore_ascii_case("html") {
+            return Err(syn::Error::new(
+                name.span(),
+                "Only the HTML 5 doctype, `<!DOCTYPE html>`, is supported",
+            ));
+        }
+        input.parse::<syn::Token![>]>()?;
+
+        Ok(Self::Doctype)
+    }
+}
+
+impl ToTokens for Declaration {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let render = crate_path();
+        match self {
+            Self::Comment(text) => quote!(#render::html::Comment(#text)),
+            Self::Doctype => quote!(#render::html::HTML5Doctype),
+        }
+        .to_tokens(tokens);
+    }
+}
@@ -1,11 +1,13 @@
 use crate::children::Children;
+use crate::crate_path::crate_path;
 use crate::element_attributes::ElementAttributes;
-use crate::tags::{ClosingTag, OpenTag};
+use crate::tags::{ClosingTag, OpenTag, TagName};
+use proc_macro2::{Ident, Span};
 use quote::{quote, ToTokens};
 use syn::parse::{Parse, ParseStream, Result};
 
 pub struct Element {
-    name: syn::Path,
+    name: TagName,
     attributes: ElementAttributes,
     children: Children,
 }
@@ -33,14 +35,7 @@ impl Parse for Element {
 
 impl Element {
     pub fn is_custom_element(&self) -> bool {
-        match self.name.get_ident() {
-            None => true,
-            Some(ident) => {
-                let name = ident.to_string();
-                let first_letter = name.get(0..1).unwrap();
-                first_letter.to_uppercase() == first_letter
-            }
-        }
+        self.name.is_custom_element()
     }
 }
 
@@ -54,11 +49,35 @@ impl ToTokens for Element {
         } else {
             let attrs = self.attributes.for_simple_element();
             let children_tuple = self.children.as_option_of_tuples_tokens();
-            quote! {
-                ::render::SimpleElement {
-                    tag_name: stringify!(#name),
-                    attributes: #attrs,
-                    contents: #children_tuple,
+            let tag_name = name.html_name();
+            let render = crate_path();
+
+            if attrs.has_interpolations() {
+                // The attributes borrow the functions writing interpolated strings, so they
+                // are built when the element renders, from values evaluated here
+                let values = attrs.bind_values();
+                let attributes = attrs.bound_attributes();
+                let contents = Ident::new("contents", Span::mixed_site());
+                let writer = Ident::new("writer", Span::mixed_site());
+                quote! {{
+                    #values
+                    let #contents = #children_tuple;
+                    #render::Scope(move |#writer: &mut dyn ::std::fmt::Write| {
+                        let element = #render::SimpleElement {
+                            tag_name: #tag_name,
+                            attributes: #attributes,
+                            contents: #contents,
+                        };
+                        #render::Render::render_into(element, &mut &mut *#writer)
+                    })
+                }}
+            } else {
+                quote! {
+                    #render::SimpleElement {
+                        tag_name: #tag_name,
+                        attributes: #attrs,
+                        contents: #children_tuple,
+                    }
                 }
             }
         };
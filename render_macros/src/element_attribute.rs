@@ -1,30 +1,200 @@
-use quote::quote;
+use crate::crate_path::crate_path;
+use crate::interpolation::Interpolation;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, ToTokens};
 use std::hash::{Hash, Hasher};
+use syn::buffer::Cursor;
 use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream, Result};
-use syn::spanned::Spanned;
 
-pub type AttributeKey = syn::punctuated::Punctuated<syn::Ident, syn::Token![-]>;
+/// An attribute name, like `class`, `data-id`, `xlink:href`, `@click`, `:class`,
+/// `x-on:click.prevent` or `hx-on::after-request`.
+///
+/// Names are made of identifiers and numbers, separated by `-`, `:` and `.`,
+/// and can start with `@` or `:`.
+pub struct AttributeKey {
+    /// The name as it is written in HTML
+    name: String,
+    /// The identifiers and numbers in the name, without separators
+    parts: Vec<String>,
+    tokens: TokenStream,
+}
+
+impl AttributeKey {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(syn::Ident::peek_any) || input.peek(syn::Token![@]) || input.peek(syn::Token![:])
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the name is a single identifier, so it can be a variable or a field
+    pub fn is_ident(&self) -> bool {
+        self.parts.len() == 1 && self.name == self.parts[0]
+    }
+
+    /// Whether the name is made of identifiers separated by dashes, like `data-id`
+    fn is_dashed(&self) -> bool {
+        self.parts.join("-") == self.name
+    }
+}
+
+impl Parse for AttributeKey {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.step(|cursor| {
+            let mut name = String::new();
+            let mut parts = vec![];
+            let mut tokens = TokenStream::new();
+            let mut rest = *cursor;
+
+            if let Some((punct, next)) = rest.punct() {
+                if punct.as_char() == '@' || punct.as_char() == ':' {
+                    name.push(punct.as_char());
+                    tokens.extend(Some(TokenTree::Punct(punct)));
+                    rest = next;
+                }
+            }
+
+            loop {
+                let (part, token, next) = name_part(rest).ok_or_else(|| {
+                    let message = if name.is_empty() {
+                        "Expected an attribute name"
+                    } else {
+                        "Expected an identifier or a number after the separator"
+                    };
+                    syn::Error::new(rest.span(), message)
+                })?;
+                tokens.extend(Some(token));
+                rest = next;
+                name.push_str(&part);
+                parts.push(part);
+
+                let mut has_separator = false;
+                while let Some((punct, next)) = rest.punct() {
+                    if !matches!(punct.as_char(), '-' | ':' | '.') {
+                        break;
+                    }
+                    name.push(punct.as_char());
+                    tokens.extend(Some(TokenTree::Punct(punct)));
+                    rest = next;
+                    has_separator = true;
+                }
+                if !has_separator {
+                    break;
+                }
+            }
+
+            Ok((
+                Self {
+                    name,
+                    parts,
+                    tokens,
+                },
+                rest,
+            ))
+        })
+    }
+}
+
+/// An identifier or a number in an attribute name, like `level` or `2` in `data-level-2`
+fn name_part(cursor: Cursor) -> Option<(String, TokenTree, Cursor)> {
+    if let Some((ident, next)) = cursor.ident() {
+        return Some((ident.unraw().to_string(), TokenTree::Ident(ident), next));
+    }
+
+    let (literal, next) = cursor.literal()?;
+    let part = literal.to_string();
+    if part.starts_with(|c: char| c.is_ascii_digit()) {
+        Some((part, TokenTree::Literal(literal), next))
+    } else {
+        None
+    }
+}
+
+impl ToTokens for AttributeKey {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.tokens.to_tokens(tokens);
+    }
+}
 
 pub enum ElementAttribute {
     Punned(AttributeKey),
-    WithValue(AttributeKey, syn::Block),
+    WithValue(AttributeKey, AttributeValue),
 }
 
-impl ElementAttribute {
-    pub fn ident(&self) -> &AttributeKey {
-        match self {
-            Self::Punned(ident) | Self::WithValue(ident, _) => ident,
+pub enum AttributeValue {
+    /// `class={"btn"}`
+    Block(syn::Block),
+    /// `class="btn"` or `tabindex=1`
+    Literal(syn::Lit),
+    /// `class=self.class` or `class=props.class()`
+    Expression(proc_macro2::TokenStream),
+    /// `class="btn btn-{kind}"`
+    Interpolated(Interpolation),
+}
+
+impl AttributeValue {
+    /// Parses a path, followed by any number of fields and method calls
+    fn parse_expression(input: ParseStream) -> Result<proc_macro2::TokenStream> {
+        let path = syn::Path::parse_mod_style(input)?;
+        let mut expression = quote!(#path);
+
+        while input.peek(syn::Token![.]) && !input.peek(syn::Token![..]) {
+            let dot = input.parse::<syn::Token![.]>()?;
+            let member = input.parse::<syn::Member>()?;
+            expression = quote!(#expression #dot #member);
+
+            if input.peek(syn::token::Paren) {
+                let group = input.parse::<proc_macro2::Group>()?;
+                expression = quote!(#expression #group);
+            }
         }
+
+        Ok(expression)
     }
+}
+
+impl Parse for AttributeValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(syn::token::Brace) {
+            return Ok(Self::Block(input.parse()?));
+        }
+
+        if input.peek(syn::Lit) {
+            return match input.parse::<syn::Lit>()? {
+                syn::Lit::Str(string) => match Interpolation::parse(&string)? {
+                    Some(interpolation) => Ok(Self::Interpolated(interpolation)),
+                    None => {
+                        let value = string.value().replace("{{", "{").replace("}}", "}");
+                        let string = syn::LitStr::new(&value, string.span());
+                        Ok(Self::Literal(syn::Lit::Str(string)))
+                    }
+                },
+                literal => Ok(Self::Literal(literal)),
+            };
+        }
+
+        if input.peek(syn::Ident::peek_any) || input.peek(syn::Token![::]) {
+            return Ok(Self::Expression(Self::parse_expression(input)?));
+        }
 
-    pub fn idents(&self) -> Vec<&syn::Ident> {
-        self.ident().iter().collect::<Vec<_>>()
+        Err(input
+            .error("Expected an attribute value, like `{value}`, a literal or a path to a value"))
     }
+}
 
+impl ElementAttribute {
+    pub fn key(&self) -> &AttributeKey {
+        match self {
+            Self::Punned(key) | Self::WithValue(key, _) => key,
+        }
+    }
+
+    /// The value, as it is passed to a custom element
     pub fn value_tokens(&self) -> proc_macro2::TokenStream {
         match self {
-            Self::WithValue(_, value) => {
+            Self::WithValue(_, AttributeValue::Block(value)) => {
                 if value.stmts.len() == 1 {
                     let first = &value.stmts[0];
                     quote!(#first)
@@ -32,7 +202,44 @@ impl ElementAttribute {
                     quote!(#value)
                 }
             }
-            Self::Punned(ident) => quote!(#ident),
+            Self::WithValue(_, AttributeValue::Literal(literal)) => quote!(#literal),
+            Self::WithValue(_, AttributeValue::Expression(expression)) => expression.clone(),
+            Self::WithValue(_, AttributeValue::Interpolated(interpolation)) => {
+                interpolation.format_tokens()
+            }
+            Self::Punned(key) => quote!(#key),
+        }
+    }
+
+    /// The interpolated string, if the value is one
+    pub fn interpolation(&self) -> Option<&Interpolation> {
+        match self {
+            Self::WithValue(_, AttributeValue::Interpolated(interpolation)) => Some(interpolation),
+            _ => None,
+        }
+    }
+
+    /// The value, as it is passed to a simple element: literals become strings, which event
+    /// handlers trust since they are part of the template.
+    /// Interpolated strings are written by the element instead, see `SimpleElementAttributes`.
+    pub fn simple_element_value_tokens(&self, is_event_handler: bool) -> proc_macro2::TokenStream {
+        let render = crate_path();
+        let value = match self {
+            Self::WithValue(_, AttributeValue::Literal(literal)) if is_event_handler => {
+                let string = literal_string(literal);
+                quote!(#render::TrustedScript::new(#string))
+            }
+            Self::WithValue(_, AttributeValue::Literal(literal)) => {
+                let string = literal_string(literal);
+                quote!(#string)
+            }
+            _ => self.value_tokens(),
+        };
+
+        if is_event_handler {
+            quote!(#render::AttributeValue::event_handler(#value))
+        } else {
+            quote!(#render::AttributeValue::from(#value))
         }
     }
 
@@ -45,41 +252,58 @@ impl ElementAttribute {
     }
 
     pub fn validate_for_custom_element(self) -> Result<Self> {
-        if self.idents().len() < 2 {
+        let key = self.key();
+        if key.is_ident() {
             Ok(self)
-        } else {
-            let alternative_name = self
-                .idents()
-                .iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<_>>()
-                .join("_");
-
+        } else if key.is_dashed() {
             let error_message = format!(
                 "Can't use dash-delimited values on custom components. Did you mean `{}`?",
-                alternative_name
+                key.parts.join("_")
             );
-
-            Err(syn::Error::new(self.ident().span(), error_message))
+            Err(syn::Error::new_spanned(key, error_message))
+        } else {
+            let error_message = format!(
+                "Can't use `{}` on custom components, since props are struct fields",
+                key.name()
+            );
+            Err(syn::Error::new_spanned(key, error_message))
         }
     }
 
     pub fn validate_for_simple_element(self) -> Result<Self> {
-        match (&self, self.idents().len()) {
-            (Self::Punned(ref key), len) if len > 1 => {
+        match &self {
+            Self::Punned(key) if key.is_dashed() && !key.is_ident() => {
                 let error_message = "Can't use punning with dash-delimited values";
-                Err(syn::Error::new(key.span(), error_message))
+                Err(syn::Error::new_spanned(key, error_message))
+            }
+            Self::Punned(key) if !key.is_ident() => {
+                let error_message = format!(
+                    "Can't use punning with `{}`, which isn't a variable name",
+                    key.name()
+                );
+                Err(syn::Error::new_spanned(key, error_message))
             }
             _ => Ok(self),
         }
     }
 }
 
+/// The text of a literal, so `tabindex=1` renders like `tabindex="1"`
+fn literal_string(literal: &syn::Lit) -> syn::LitStr {
+    let value = match literal {
+        syn::Lit::Str(string) => string.value(),
+        syn::Lit::Int(int) => int.base10_digits().to_owned(),
+        syn::Lit::Float(float) => float.base10_digits().to_owned(),
+        syn::Lit::Bool(boolean) => boolean.value.to_string(),
+        syn::Lit::Char(c) => c.value().to_string(),
+        literal => quote!(#literal).to_string(),
+    };
+    syn::LitStr::new(&value, syn::spanned::Spanned::span(literal))
+}
+
 impl PartialEq for ElementAttribute {
     fn eq(&self, other: &Self) -> bool {
-        let self_idents: Vec<_> = self.ident().iter().collect();
-        let other_idents: Vec<_> = other.ident().iter().collect();
-        self_idents == other_idents
+        self.key().name() == other.key().name()
     }
 }
 
@@ -87,14 +311,13 @@ impl Eq for ElementAttribute {}
 
 impl Hash for ElementAttribute {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let ident = self.idents();
-        Hash::hash(&ident, state)
+        Hash::hash(self.key().name(), state)
     }
 }
 
 impl Parse for ElementAttribute {
     fn parse(input: ParseStream) -> Result<Self> {
-        let name = AttributeKey::parse_separated_nonempty_with(input, syn::Ident::parse_any)?;
+        let name = input.parse::<AttributeKey>()?;
         let not_punned = input.peek(syn::Token![=]);
 
         if !not_punned {
@@ -102,7 +325,7 @@ impl Parse for ElementAttribute {
         }
 
         input.parse::<syn::Token![=]>()?;
-        let value = input.parse::<syn::Block>()?;
+        let value = input.parse::<AttributeValue>()?;
 
         Ok(Self::WithValue(name, value))
     }
@@ -1,22 +1,58 @@
 use crate::children::Children;
-use crate::element_attribute::ElementAttribute;
+use crate::crate_path::crate_path;
+use crate::element_attribute::{AttributeKey, ElementAttribute};
+use proc_macro2::{Delimiter, Ident, Spacing, Span};
 use proc_macro_error::emit_error;
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use std::collections::HashSet;
-use syn::ext::IdentExt;
-use syn::parse::{Parse, ParseStream, Result};
-use syn::spanned::Spanned;
+use syn::parse::{ParseStream, Result};
 
-pub type Attributes = HashSet<ElementAttribute>;
+pub type Attributes = Vec<ElementAttribute>;
+
+/// Attributes spread from a value, like `{..attributes}`
+pub struct Spread {
+    pub value: syn::Expr,
+    /// How many attributes come before it
+    pub position: usize,
+}
+
+impl Spread {
+    pub fn peek(input: ParseStream) -> bool {
+        match input.cursor().group(Delimiter::Brace) {
+            Some((content, _, _)) => match content.punct() {
+                Some((first, rest))
+                    if first.as_char() == '.' && first.spacing() == Spacing::Joint =>
+                {
+                    matches!(rest.punct(), Some((second, _)) if second.as_char() == '.')
+                }
+                _ => false,
+            },
+            None => false,
+        }
+    }
+
+    fn parse(input: ParseStream, position: usize) -> Result<Self> {
+        let content;
+        syn::braced!(content in input);
+        content.parse::<syn::Token![..]>()?;
+        let value = content.parse::<syn::Expr>()?;
+        Ok(Self { value, position })
+    }
+}
 
 #[derive(Default)]
 pub struct ElementAttributes {
+    /// The attributes, in order
     pub attributes: Attributes,
+    pub spreads: Vec<Spread>,
 }
 
 impl ElementAttributes {
-    pub fn new(attributes: Attributes) -> Self {
-        Self { attributes }
+    pub fn new(attributes: Attributes, spreads: Vec<Spread>) -> Self {
+        Self {
+            attributes,
+            spreads,
+        }
     }
 
     pub fn for_custom_element<'c>(
@@ -25,6 +61,7 @@ impl ElementAttributes {
     ) -> CustomElementAttributes<'_, 'c> {
         CustomElementAttributes {
             attributes: &self.attributes,
+            spread: self.spreads.first(),
             children,
         }
     }
@@ -32,49 +69,64 @@ impl ElementAttributes {
     pub fn for_simple_element(&self) -> SimpleElementAttributes<'_> {
         SimpleElementAttributes {
             attributes: &self.attributes,
+            spreads: &self.spreads,
         }
     }
 
     pub fn parse(input: ParseStream, is_custom_element: bool) -> Result<Self> {
-        let mut parsed_self = input.parse::<Self>()?;
+        let mut attributes = Attributes::new();
+        let mut spreads = vec![];
+        let mut names = HashSet::new();
 
-        let new_attributes: Attributes = parsed_self
-            .attributes
-            .drain()
-            .filter_map(|attribute| match attribute.validate(is_custom_element) {
-                Ok(x) => Some(x),
-                Err(err) => {
-                    emit_error!(err.span(), "Invalid attribute: {}", err);
-                    None
+        loop {
+            if Spread::peek(input) {
+                let spread = Spread::parse(input, attributes.len())?;
+                if is_custom_element && !spreads.is_empty() {
+                    emit_error!(
+                        spread.value,
+                        "Only one value can be spread into a component's props"
+                    );
                 }
-            })
-            .collect();
+                spreads.push(spread);
+                continue;
+            }
 
-        Ok(ElementAttributes::new(new_attributes))
-    }
-}
+            if !AttributeKey::peek(input) {
+                break;
+            }
 
-impl Parse for ElementAttributes {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let mut attributes: HashSet<ElementAttribute> = HashSet::new();
-        while input.peek(syn::Ident::peek_any) {
             let attribute = input.parse::<ElementAttribute>()?;
-            let ident = attribute.ident();
-            if attributes.contains(&attribute) {
+            let key = attribute.key();
+            // HTML attribute names are case-insensitive, but props are struct fields
+            let name = if is_custom_element {
+                key.name().to_owned()
+            } else {
+                key.name().to_ascii_lowercase()
+            };
+            if !names.insert(name) {
                 emit_error!(
-                    ident.span(),
+                    key,
                     "There is a previous definition of the {} attribute",
-                    quote!(#ident)
+                    key.name()
                 );
+                continue;
+            }
+
+            match attribute.validate(is_custom_element) {
+                Ok(attribute) => attributes.push(attribute),
+                Err(err) => emit_error!(err.span(), "Invalid attribute: {}", err),
             }
-            attributes.insert(attribute);
         }
-        Ok(ElementAttributes::new(attributes))
+
+        Ok(ElementAttributes::new(attributes, spreads))
     }
 }
 
+/// The fields of a component's props struct. A spread value fills in the fields that aren't
+/// set, using struct update syntax.
 pub struct CustomElementAttributes<'a, 'c> {
     attributes: &'a Attributes,
+    spread: Option<&'a Spread>,
     children: &'c Children,
 }
 
@@ -84,11 +136,11 @@ impl<'a, 'c> ToTokens for CustomElementAttributes<'a, 'c> {
             .attributes
             .iter()
             .map(|attribute| {
-                let ident = attribute.ident();
+                let key = attribute.key();
                 let value = attribute.value_tokens();
 
                 quote! {
-                    #ident: #value
+                    #key: #value
                 }
             })
             .collect();
@@ -100,49 +152,170 @@ impl<'a, 'c> ToTokens for CustomElementAttributes<'a, 'c> {
             });
         }
 
-        let quoted = if attrs.len() == 0 {
-            quote!()
-        } else {
-            quote!({ #(#attrs),* })
+        let quoted = match self.spread {
+            Some(spread) => {
+                let value = &spread.value;
+                quote!({ #(#attrs,)* ..#value })
+            }
+            None if attrs.is_empty() => quote!(),
+            None => quote!({ #(#attrs),* }),
         };
 
         quoted.to_tokens(tokens);
     }
 }
 
+/// Event handlers only accept a `TrustedScript`, so untrusted strings fail to compile.
+///
+/// The same check as `is_event_handler` in render's `simple_element.rs`, which drops untrusted
+/// handlers at runtime. The proc-macro crate can't share it, so keep the two in sync: the
+/// `untrusted-event-handler` ui test covers this copy, and `drops_untrusted_event_handlers` in
+/// `simple_element.rs` covers that one.
+fn is_event_handler(name: &str) -> bool {
+    EVENT_HANDLER_PREFIXES.iter().any(|prefix| {
+        name.len() > prefix.len()
+            && name
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    })
+}
+
+/// `onclick`, and the event handlers of Alpine.js, Vue and htmx, like `@click`, `x-on:click`,
+/// `v-on:click` and `hx-on:click`
+const EVENT_HANDLER_PREFIXES: &[&str] = &["on", "@", "x-on:", "v-on:", "hx-on:", "hx-on-"];
+
+/// The attributes of a simple element, as an `Option<HashMap>` of their values.
+///
+/// Interpolated strings are written by functions that the attributes borrow. When there are
+/// any, the values are evaluated where the element is, using `bind_values`, and the
+/// attributes are built from them while the element renders, using `bound_attributes`.
 pub struct SimpleElementAttributes<'a> {
     attributes: &'a Attributes,
+    spreads: &'a [Spread],
 }
 
-impl<'a> ToTokens for SimpleElementAttributes<'a> {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        if self.attributes.is_empty() {
-            quote!(None).to_tokens(tokens);
-        } else {
-            let attrs: Vec<_> = self
-                .attributes
-                .iter()
-                .map(|attribute| {
-                    let mut iter = attribute.ident().iter();
-                    let first_word = iter.next().unwrap().unraw();
-                    let ident = iter.fold(first_word.to_string(), |acc, curr| {
-                        format!("{}-{}", acc, curr.unraw())
-                    });
-                    let value = attribute.value_tokens();
+impl<'a> SimpleElementAttributes<'a> {
+    /// Whether an attribute is an interpolated string, which the attributes borrow
+    pub fn has_interpolations(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| self.is_interpolated(attribute))
+    }
 
-                    quote! {
-                        hm.insert(#ident, ::std::borrow::Cow::from(#value));
+    fn is_interpolated(&self, attribute: &ElementAttribute) -> bool {
+        attribute.interpolation().is_some() && !is_event_handler(attribute.key().name())
+    }
+
+    /// `let` statements evaluating every value, in order
+    pub fn bind_values(&self) -> proc_macro2::TokenStream {
+        let mut values: Vec<_> = self
+            .attributes
+            .iter()
+            .enumerate()
+            .map(|(index, attribute)| {
+                let binding = value_binding("value", index);
+                let value = match attribute.interpolation() {
+                    Some(interpolation) if self.is_interpolated(attribute) => {
+                        interpolation.writer_tokens()
                     }
-                })
-                .collect();
+                    _ => attribute
+                        .simple_element_value_tokens(is_event_handler(attribute.key().name())),
+                };
+                quote!(let #binding = #value;)
+            })
+            .collect();
+
+        for (index, spread) in self.spreads.iter().enumerate().rev() {
+            let binding = value_binding("spread", index);
+            let value = &spread.value;
+            values.insert(spread.position, quote!(let #binding = #value;));
+        }
+
+        quote!(#(#values)*)
+    }
+
+    /// The attributes, made of the values evaluated by `bind_values`
+    pub fn bound_attributes(&self) -> proc_macro2::TokenStream {
+        let render = crate_path();
+        self.hashmap_tokens(
+            |index, attribute| {
+                let binding = value_binding("value", index);
+                if self.is_interpolated(attribute) {
+                    quote!(#render::AttributeValue::interpolated(&#binding))
+                } else {
+                    quote!(#binding)
+                }
+            },
+            |index, _| value_binding("spread", index).into_token_stream(),
+        )
+    }
+
+    fn hashmap_tokens(
+        &self,
+        value: impl Fn(usize, &ElementAttribute) -> proc_macro2::TokenStream,
+        spread: impl Fn(usize, &Spread) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        if self.attributes.is_empty() && self.spreads.is_empty() {
+            return quote!(None);
+        }
+
+        let render = crate_path();
+        let mut attrs: Vec<_> = self
+            .attributes
+            .iter()
+            .enumerate()
+            .map(|(index, attribute)| {
+                let name = attribute.key().name();
+                let value = value(index, attribute);
 
-            let hashmap_declaration = quote! {{
-                let mut hm = std::collections::HashMap::<&str, ::std::borrow::Cow<'_, str>>::new();
-                #(#attrs)*
-                Some(hm)
-            }};
+                // Explicit attributes replace spread ones, so they go through the same path
+                if self.spreads.is_empty() {
+                    quote! {
+                        hm.insert(::std::borrow::Cow::Borrowed(#name), #value);
+                    }
+                } else {
+                    quote! {
+                        #render::spread_attributes(&mut hm, ::std::iter::once((#name, #value)));
+                    }
+                }
+            })
+            .collect();
 
-            hashmap_declaration.to_tokens(tokens);
+        for (index, spread_value) in self.spreads.iter().enumerate().rev() {
+            let value = spread(index, spread_value);
+            attrs.insert(
+                spread_value.position,
+                quote! {
+                    #render::spread_attributes(&mut hm, #value);
+                },
+            );
         }
+
+        quote! {{
+            let mut hm = std::collections::HashMap::<
+                ::std::borrow::Cow<'_, str>,
+                #render::AttributeValue<'_>,
+            >::new();
+            #(#attrs)*
+            Some(hm)
+        }}
+    }
+}
+
+impl<'a> ToTokens for SimpleElementAttributes<'a> {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.hashmap_tokens(
+            |_, attribute| {
+                attribute.simple_element_value_tokens(is_event_handler(attribute.key().name()))
+            },
+            |_, spread| spread.value.to_token_stream(),
+        )
+        .to_tokens(tokens);
     }
 }
+
+/// A variable holding a value evaluated by `SimpleElementAttributes::bind_values`, which the
+/// element's children can't see
+fn value_binding(kind: &str, index: usize) -> Ident {
+    format_ident!("{}{}", kind, index, span = Span::mixed_site())
+}
@@ -0,0 +1,54 @@
+use crate::children::Children;
+use crate::crate_path::crate_path;
+use crate::if_else::parse_braced_children;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream, Result};
+
+/// `for pattern in iterator { children }` among children.
+///
+/// The loop runs inside a `Scope` closure when the children are rendered, so every item's
+/// children are written before the next item is taken. Since the children are expressions,
+/// `break` and `continue` aren't supported.
+pub struct ForLoop {
+    pattern: syn::Pat,
+    iterator: syn::Expr,
+    body: Children,
+}
+
+impl Parse for ForLoop {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<syn::Token![for]>()?;
+        let pattern = input.parse::<syn::Pat>()?;
+        input.parse::<syn::Token![in]>()?;
+        let iterator = input.call(syn::Expr::parse_without_eager_brace)?;
+        let body = parse_braced_children(input)?;
+
+        Ok(Self {
+            pattern,
+            iterator,
+            body,
+        })
+    }
+}
+
+impl ToTokens for ForLoop {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let pattern = &self.pattern;
+        let iterator = &self.iterator;
+        let body = self.body.as_renderable_tokens();
+        let render = crate_path();
+        // Not visible to the children, which may have their own `writer`
+        let writer = Ident::new("writer", Span::mixed_site());
+
+        quote! {
+            #render::Scope(|#writer: &mut dyn ::std::fmt::Write| {
+                for #pattern in #iterator {
+                    #render::Render::render_into(#body, &mut &mut *#writer)?;
+                }
+                ::std::result::Result::Ok(())
+            })
+        }
+        .to_tokens(tokens);
+    }
+}
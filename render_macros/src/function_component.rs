@@ -1,3 +1,4 @@
+use crate::crate_path::crate_path;
 use proc_macro::TokenStream;
 use proc_macro_error::emit_error;
 use quote::quote;
@@ -9,8 +10,9 @@ pub fn create_function_component(f: syn::ItemFn) -> TokenStream {
     let inputs = f.sig.inputs;
     let block = f.block;
     let vis = f.vis;
+    let render = crate_path();
 
-    let inputs_block = if inputs.len() > 0 {
+    let inputs_block = if !inputs.is_empty() {
         let input_names: Vec<_> = inputs.iter().collect();
 
         quote!({ #(#vis #input_names),* })
@@ -18,7 +20,7 @@ pub fn create_function_component(f: syn::ItemFn) -> TokenStream {
         quote!(;)
     };
 
-    let inputs_reading = if inputs.len() == 0 {
+    let inputs_reading = if inputs.is_empty() {
         quote!()
     } else {
         let input_names: Vec<_> = inputs
@@ -44,13 +46,13 @@ pub fn create_function_component(f: syn::ItemFn) -> TokenStream {
         #[derive(Debug)]
         #vis struct #struct_name#impl_generics #inputs_block
 
-        impl#impl_generics ::render::Render for #struct_name #ty_generics #where_clause {
+        impl#impl_generics #render::Render for #struct_name #ty_generics #where_clause {
             fn render_into<W: std::fmt::Write>(self, w: &mut W) -> std::fmt::Result {
                 let result = {
                     #inputs_reading
                     #block
                 };
-                ::render::Render::render_into(result, w)
+                #render::Render::render_into(result, w)
             }
         }
     })
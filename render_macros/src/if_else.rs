@@ -0,0 +1,84 @@
+use crate::children::Children;
+use crate::crate_path::crate_path;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream, Result};
+
+/// `if cond { children } else if cond { children } else { children }` among children.
+///
+/// Each branch is wrapped in nested `Either`s, so branches can render different types.
+/// A missing `else` renders nothing.
+pub struct IfElse {
+    branches: Vec<(syn::Expr, Children)>,
+    otherwise: Option<Children>,
+}
+
+/// Parses `{ children }`
+pub fn parse_braced_children(input: ParseStream) -> Result<Children> {
+    let content;
+    syn::braced!(content in input);
+    content.parse()
+}
+
+impl Parse for IfElse {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut branches = vec![];
+        let mut otherwise = None;
+
+        loop {
+            input.parse::<syn::Token![if]>()?;
+            let condition = input.call(syn::Expr::parse_without_eager_brace)?;
+            branches.push((condition, parse_braced_children(input)?));
+
+            if !input.peek(syn::Token![else]) {
+                break;
+            }
+            input.parse::<syn::Token![else]>()?;
+
+            if !input.peek(syn::Token![if]) {
+                otherwise = Some(parse_braced_children(input)?);
+                break;
+            }
+        }
+
+        Ok(Self {
+            branches,
+            otherwise,
+        })
+    }
+}
+
+/// Wraps the branch at `index` out of `count` in `Either`s:
+/// `Left(a)`, `Right(Left(b))`, ..., `Right(Right(z))`
+pub fn either_tokens(index: usize, count: usize, value: TokenStream) -> TokenStream {
+    let render = crate_path();
+    let wrapped = if index + 1 == count {
+        value
+    } else {
+        quote!(#render::Either::Left(#value))
+    };
+
+    (0..index).fold(wrapped, |value, _| quote!(#render::Either::Right(#value)))
+}
+
+impl ToTokens for IfElse {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let count = self.branches.len() + 1;
+        let branches = self
+            .branches
+            .iter()
+            .enumerate()
+            .map(|(index, (condition, children))| {
+                let value = either_tokens(index, count, children.as_renderable_tokens());
+                quote!(if #condition { #value })
+            });
+
+        let otherwise = match &self.otherwise {
+            Some(children) => children.as_renderable_tokens(),
+            None => quote!(()),
+        };
+        let otherwise = either_tokens(count - 1, count, otherwise);
+
+        quote!(#(#branches)else* else { #otherwise }).to_tokens(tokens);
+    }
+}
@@ -0,0 +1,126 @@
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote_spanned, ToTokens};
+
+/// A string literal with `{expression}` placeholders, like `"btn btn-{kind}"`.
+/// Literal braces are written as `{{` and `}}`, like in `format!`.
+pub struct Interpolation {
+    format: String,
+    args: Vec<TokenStream>,
+    span: Span,
+}
+
+impl Interpolation {
+    /// Parses the placeholders of a string literal, or returns `None` if it has none
+    pub fn parse(literal: &syn::LitStr) -> syn::Result<Option<Self>> {
+        let value = literal.value();
+        let span = literal.span();
+        let error = |message: &str| syn::Error::new(span, message);
+
+        let mut format = String::new();
+        let mut args = vec![];
+        let mut chars = value.char_indices().peekable();
+
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|(_, c)| *c) == Some('{') => {
+                    chars.next();
+                    format.push_str("{{");
+                }
+                '}' if chars.peek().map(|(_, c)| *c) == Some('}') => {
+                    chars.next();
+                    format.push_str("}}");
+                }
+                '}' => return Err(error("Unmatched `}` in attribute value. Use `}}` instead")),
+                '{' => {
+                    let mut depth = 1;
+                    let start = index + 1;
+                    let end = loop {
+                        match chars.next() {
+                            Some((_, '{')) => depth += 1,
+                            Some((end, '}')) if depth == 1 => break end,
+                            Some((_, '}')) => depth -= 1,
+                            Some(_) => {}
+                            None => {
+                                return Err(error(
+                                    "Unmatched `{` in attribute value. Use `{{` instead",
+                                ))
+                            }
+                        }
+                    };
+
+                    let expression =
+                        syn::parse_str::<syn::Expr>(&value[start..end]).map_err(|err| {
+                            error(&format!("Invalid expression in attribute value: {}", err))
+                        })?;
+                    let expression = if is_place(&expression) {
+                        quote_spanned!(span=> &#expression)
+                    } else {
+                        expression.into_token_stream()
+                    };
+                    args.push(respan(expression, span));
+                    format.push_str("{}");
+                }
+                c => format.push(c),
+            }
+        }
+
+        if args.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(Self { format, args, span }))
+        }
+    }
+
+    /// An allocated `String`, for custom elements
+    pub fn format_tokens(&self) -> TokenStream {
+        let format = &self.format;
+        let args = &self.args;
+        quote_spanned!(self.span=> ::std::format!(#format, #(#args),*))
+    }
+
+    /// A function writing the string, for `AttributeValue::interpolated`.
+    /// The expressions are evaluated where the attribute is. Like in `format!`, variables and
+    /// fields are borrowed, and other values are moved into the function, which can then
+    /// write them any number of times.
+    pub fn writer_tokens(&self) -> TokenStream {
+        let format = &self.format;
+        let args = &self.args;
+        let bindings: Vec<_> = (0..args.len()).map(|i| format_ident!("arg{}", i)).collect();
+
+        quote_spanned!(self.span=> {
+            let args = (#(#args,)*);
+            move |writer: &mut dyn ::std::fmt::Write| {
+                let (#(#bindings,)*) = &args;
+                ::std::write!(writer, #format, #(#bindings),*)
+            }
+        })
+    }
+}
+
+/// Whether an expression is a variable or a field, which is borrowed instead of moved
+fn is_place(expression: &syn::Expr) -> bool {
+    match expression {
+        syn::Expr::Path(_) => true,
+        syn::Expr::Field(field) => is_place(&field.base),
+        syn::Expr::Index(index) => is_place(&index.expr),
+        syn::Expr::Paren(paren) => is_place(&paren.expr),
+        _ => false,
+    }
+}
+
+/// Points every token of a parsed expression at the literal it came from
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
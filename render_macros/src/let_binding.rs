@@ -0,0 +1,50 @@
+use crate::children::Children;
+use crate::crate_path::crate_path;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream, Result};
+
+/// `let pattern = value;` among children, followed by the siblings that can use it.
+///
+/// The siblings are rendered inside a `Scope` closure, so they can borrow the binding. Like
+/// the siblings' other variables, the ones the value uses are borrowed, and the value is
+/// computed when the siblings are rendered.
+pub struct Let {
+    local: syn::Local,
+    body: Children,
+}
+
+impl Parse for Let {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let local = match input.parse::<syn::Stmt>()? {
+            syn::Stmt::Local(local) => local,
+            statement => {
+                return Err(syn::Error::new_spanned(
+                    statement,
+                    "Expected a `let` statement",
+                ))
+            }
+        };
+        let body = input.parse::<Children>()?;
+
+        Ok(Self { local, body })
+    }
+}
+
+impl ToTokens for Let {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let local = &self.local;
+        let body = self.body.as_renderable_tokens();
+        // Not visible to the children, which may have their own `writer`
+        let writer = Ident::new("writer", Span::mixed_site());
+        let render = crate_path();
+
+        quote! {
+            #render::Scope(|#writer: &mut dyn ::std::fmt::Write| {
+                #local
+                #render::Render::render_into(#body, &mut { #writer })
+            })
+        }
+        .to_tokens(tokens);
+    }
+}
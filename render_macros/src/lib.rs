@@ -2,16 +2,27 @@ extern crate proc_macro;
 
 mod child;
 mod children;
+mod crate_path;
+mod declaration;
 mod element;
 mod element_attribute;
 mod element_attributes;
+mod entities;
+mod for_loop;
 mod function_component;
+mod if_else;
+mod interpolation;
+mod let_binding;
+mod match_arms;
 mod tags;
+mod text;
 
-use element::Element;
+use children::Children;
+use crate_path::{crate_path, with_crate_path};
 use proc_macro::TokenStream;
 use proc_macro_error::proc_macro_error;
 use quote::quote;
+use syn::parse::Parser;
 use syn::parse_macro_input;
 
 /// Render a component tree to an HTML string, using XML-like tags.
@@ -20,11 +31,160 @@ use syn::parse_macro_input;
 ///
 /// ### Simple HTML elements start with a lowercase
 ///
+/// Web components, whose names have dashes, are simple elements as well.
+///
 /// ```rust
 /// # use pretty_assertions::assert_eq;
 /// # use render_macros::html;
 /// let rendered = html! { <div id={"main"}>{"Hello"}</div> };
 /// assert_eq!(rendered, r#"<div id="main">Hello</div>"#);
+///
+/// let rendered = html! { <sl-button variant={"primary"}>Save</sl-button> };
+/// assert_eq!(rendered, r#"<sl-button variant="primary">Save</sl-button>"#);
+/// ```
+///
+/// ### Text can be written as is
+///
+/// Bare words and string literals are rendered as escaped text. Since Rust doesn't keep the
+/// whitespace between tokens, words are separated by a single space, except around
+/// punctuation like `,`, `(` or the `-` in `well-known`, and text is separated from the
+/// elements and blocks next to it the same way. String literals are written as they are,
+/// without a space between them and the elements or blocks next to them, so use one for
+/// exact whitespace, or for text Rust can't tokenize, like an unbalanced quote. Words like
+/// `for` or `if` are text unless they start a full loop or condition. Character references, like `&nbsp;` or `&#169;`,
+/// are decoded at compile time, and unknown or invalid ones fail the compilation. Since that
+/// includes text like `Tom & Jerry;`, write an `&` followed by a word and a `;` as `{"&"}`
+/// or in a string literal.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::html;
+/// let name = "Gal";
+/// let rendered = html! {
+///     <p>
+///         Hello, <b>{name}</b>! Is it "<3"? &mdash;&#x1F60A;
+///     </p>
+/// };
+/// assert_eq!(rendered, "<p>Hello, <b>Gal</b>! Is it &lt;3? —😊</p>");
+/// ```
+///
+/// ### Children can be conditional
+///
+/// `if` and `else` branches can render different elements. Without an `else`, nothing is
+/// rendered when the condition is false.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::html;
+/// let unread = 3;
+/// let rendered = html! {
+///     <p>
+///         if unread == 0 {
+///             No new messages
+///         } else if unread == 1 {
+///             <b>One new message</b>
+///         } else {
+///             <b>{unread}</b> new messages
+///         }
+///     </p>
+/// };
+/// assert_eq!(rendered, "<p><b>3</b> new messages</p>");
+/// ```
+///
+/// ### Children can be repeated
+///
+/// `for` loops render their children for every item, without collecting them first.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::html;
+/// let links = [("Home", "/"), ("About", "/about")];
+/// let rendered = html! {
+///     <nav>
+///         for &(title, href) in links.iter() {
+///             <a href>{title}</a>
+///         }
+///     </nav>
+/// };
+/// assert_eq!(rendered, r#"<nav><a href="/">Home</a><a href="/about">About</a></nav>"#);
+/// ```
+///
+/// ### Children can match a value
+///
+/// Like `if` branches, `match` arms can render different elements. An arm renders an element,
+/// a string literal or children in braces, and supports guards and bindings. Since braces hold
+/// children, a value is written as `{{value}}` there.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::html;
+/// enum Status { Active { since: u16 }, Banned(&'static str), Pending }
+///
+/// let badge = |status| html! {
+///     <span>
+///         match status {
+///             Status::Active { since } if since < 2000 => <b>Veteran</b>,
+///             Status::Active { since } => { Member since {since} }
+///             Status::Banned(reason) => <i>{reason}</i>,
+///             Status::Pending => "Pending",
+///         }
+///     </span>
+/// };
+///
+/// assert_eq!(badge(Status::Active { since: 1999 }), "<span><b>Veteran</b></span>");
+/// assert_eq!(badge(Status::Active { since: 2020 }), "<span>Member since 2020</span>");
+/// assert_eq!(badge(Status::Banned("spam")), "<span><i>spam</i></span>");
+/// assert_eq!(badge(Status::Pending), "<span>Pending</span>");
+/// ```
+///
+/// ### Children can bind values
+///
+/// A `let` statement binds a value for the siblings that follow it, which can borrow it.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::html;
+/// let user = ("Ada", "Lovelace");
+///
+/// let rendered = html! {
+///     <div>
+///         let (first, last) = user;
+///         let full_name = format!("{} {}", first, last);
+///         <img alt={full_name.as_str()} />
+///         <p>Hello {first}, or {full_name.as_str()}</p>
+///     </div>
+/// };
+///
+/// assert_eq!(rendered, r#"<div><img alt="Ada Lovelace"/><p>Hello Ada, or Ada Lovelace</p></div>"#);
+/// ```
+///
+/// ### Comments and the doctype can be written as is
+///
+/// A comment holds a string literal or a block, and is escaped so it can't end early.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::html;
+/// let rendered = html! {
+///     <>
+///         <!DOCTYPE html>
+///         <!-- "Rendered by render" -->
+///         <html />
+///     </>
+/// };
+/// assert_eq!(rendered, "<!DOCTYPE html><!--Rendered by render--><html/>");
+/// ```
+///
+/// ### Multiple root nodes don't need a fragment
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::html;
+/// let rendered = html! {
+///     <!DOCTYPE html>
+///     <html lang="en" />
+/// };
+/// assert_eq!(rendered, r#"<!DOCTYPE html><html lang="en"/>"#);
 /// ```
 ///
 /// ### Custom components start with an uppercase
@@ -48,20 +208,78 @@ use syn::parse_macro_input;
 /// assert_eq!(rendered, r#"<h1>Hello world!</h1>"#);
 /// ```
 ///
-/// ### Values are always surrounded by curly braces
+/// ### Generic components use a turbofish
+///
+/// Like in expressions, generic arguments are written after `::`, and the closing tag can
+/// leave them out. Inside an `impl`, `Self` can be used as the tag name.
+///
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// # use render_macros::{html, rsx};
+/// use render::Render;
+///
+/// #[derive(Debug)]
+/// struct List<T> { items: Vec<T> }
+///
+/// impl<T: Render> Render for List<T> {
+///     fn render_into<W: std::fmt::Write>(self, writer: &mut W) -> std::fmt::Result {
+///         let items = self.items;
+///         Render::render_into(rsx! { <ul>for item in items { <li>{item}</li> }</ul> }, writer)
+///     }
+/// }
+///
+/// impl List<&'static str> {
+///     fn empty() -> impl Render {
+///         rsx! { <Self items={Vec::new()} /> }
+///     }
+/// }
+///
+/// let rendered = html! {
+///     <List::<&str> items={vec!["a", "b"]}></List>
+/// };
+///
+/// assert_eq!(rendered, r#"<ul><li>a</li><li>b</li></ul>"#);
+/// assert_eq!(List::empty().render(), r#"<ul></ul>"#);
+/// ```
+///
+/// ### Values are literals, paths or blocks
+///
+/// Literals are rendered as text, and string literals can interpolate expressions like
+/// `format!`. On simple elements, the interpolated string is written straight into the
+/// output, without allocating.
 ///
 /// ```rust
 /// # use render_macros::html;
 /// # use pretty_assertions::assert_eq;
+/// struct Button { kind: &'static str, label: &'static str }
+/// let button = Button { kind: "primary", label: "Save" };
+///
 /// let rendered = html! {
-///     <div id={"main"} />
+///     <form>
+///         <button class="btn btn-{button.kind}">{button.label}</button>
+///         <input title=button.label />
+///         <input tabindex=1 />
+///         <input value={button.label.to_uppercase()} />
+///     </form>
 /// };
 ///
-/// assert_eq!(rendered, r#"<div id="main"/>"#);
+/// assert_eq!(
+///     rendered,
+///     concat!(
+///         r#"<form><button class="btn btn-primary">Save</button>"#,
+///         r#"<input title="Save"/><input tabindex="1"/><input value="SAVE"/></form>"#
+///     )
+/// );
 /// ```
 ///
 /// ### HTML entities can accept dashed-separated value
 ///
+/// Attribute names can also have colons, dots and a leading `@` or `:`, for XML namespaces
+/// and frameworks like Alpine.js or htmx. Their event handlers, like `@click`, `x-on:click`,
+/// `v-on:click` and `hx-on:click`, are checked like `onclick`: they only accept a literal or a
+/// [`TrustedScript`](../render/struct.TrustedScript.html). Other directives that run
+/// JavaScript, like `:class` or `x-data`, are not checked, so don't give them untrusted input.
+///
 /// ```rust
 /// # use render_macros::html;
 /// # use pretty_assertions::assert_eq;
@@ -70,6 +288,47 @@ use syn::parse_macro_input;
 /// };
 ///
 /// assert_eq!(rendered, r#"<div data-testid="sometestid"/>"#);
+///
+/// let rendered = html! {
+///     <button @click.prevent="open = true" />
+/// };
+///
+/// assert_eq!(rendered, r#"<button @click.prevent="open = true"/>"#);
+/// ```
+///
+/// ### Attributes can be spread
+///
+/// On simple elements, `{..attributes}` adds every name and value pair of an iterator. When two
+/// attributes have the same name, ignoring case, the one that comes last wins.
+///
+/// ```rust
+/// # use render_macros::html;
+/// # use pretty_assertions::assert_eq;
+/// let defaults = [("class", "card")];
+///
+/// let rendered = html! {
+///     <div {..defaults} class={"card card-wide"} />
+/// };
+///
+/// assert_eq!(rendered, r#"<div class="card card-wide"/>"#);
+/// ```
+///
+/// On custom components, `{..props}` fills in the props that aren't set with the fields of an
+/// existing props value, like Rust's struct update syntax.
+///
+/// ```rust
+/// # use render_macros::{component, html, rsx};
+/// # use pretty_assertions::assert_eq;
+/// #[component]
+/// fn Button<'a>(label: &'a str, kind: &'a str) {
+///     rsx! { <button class={kind}>{label}</button> }
+/// }
+///
+/// let save = Button { label: "Save", kind: "primary" };
+///
+/// let rendered = html! { <Button {..save} label={"Save all"} /> };
+///
+/// assert_eq!(rendered, r#"<button class="primary">Save all</button>"#);
 /// ```
 ///
 /// ### Custom components can't accept dashed-separated values
@@ -109,28 +368,80 @@ use syn::parse_macro_input;
 ///
 /// assert_eq!(rendered, r#"<div class="some_class"/>"#);
 /// ```
+///
+/// ### The path to render can be overridden
+///
+/// The generated code refers to `::render`. When render is re-exported or renamed, start
+/// with `crate = path;` instead. A framework can pass `$crate::...` from its own macros,
+/// so its users don't depend on render directly.
+///
+/// ```rust
+/// # use render_macros::{component, html, rsx};
+/// # use pretty_assertions::assert_eq;
+/// mod framework {
+///     pub use render;
+/// }
+///
+/// #[component(crate = "framework::render")]
+/// fn Greeting<'a>(name: &'a str) {
+///     rsx! { crate = framework::render; <b>{format!("Hello, {}", name)}</b> }
+/// }
+///
+/// let rendered = html! {
+///     crate = framework::render;
+///     <!-- "greeting" -->
+///     <Greeting name={"Gal"} />
+/// };
+///
+/// assert_eq!(rendered, r#"<!--greeting--><b>Hello, Gal</b>"#);
+/// ```
 #[proc_macro]
 #[proc_macro_error]
 pub fn html(input: TokenStream) -> TokenStream {
-    let el = proc_macro2::TokenStream::from(rsx(input));
-    let result = quote! { ::render::Render::render(#el) };
-    TokenStream::from(result)
+    expand_root(input, |root| {
+        let render = crate_path();
+        quote! { #render::Render::render(#root) }
+    })
 }
 
-/// Generate a renderable component tree, before rendering it
+/// Generate a renderable component tree, before rendering it.
+///
+/// It accepts the same syntax as [`html!`](macro.html.html). Multiple root nodes are wrapped
+/// in a [`Fragment`](../render/struct.Fragment.html), as if they were inside `<>...</>`.
 #[proc_macro]
 #[proc_macro_error]
 pub fn rsx(input: TokenStream) -> TokenStream {
-    let el = parse_macro_input!(input as Element);
-    let result = quote! { #el };
-    TokenStream::from(result)
+    expand_root(input, |root| root)
+}
+
+/// Parses the `rsx!` syntax, with an optional `crate = ...;` header, and wraps its tokens
+fn expand_root(
+    input: TokenStream,
+    wrap: impl FnOnce(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let path = crate_path::parse_override(input)?;
+        if path.is_some() {
+            input.parse::<syn::Token![;]>()?;
+        }
+
+        with_crate_path(path, || {
+            let children = Children::parse_root(input)?;
+            Ok(wrap(children.as_root_tokens()))
+        })
+    };
+
+    match parser.parse(input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(error) => TokenStream::from(error.to_compile_error()),
+    }
 }
 
 /// A syntactic sugar for implementing [`Render`](../render/trait.Render.html) conveniently
 /// using functions.
 ///
 /// This attribute should be above a stand-alone function definition that returns a
-/// [`String`](std::string::String):
+/// [`String`]:
 ///
 /// ```rust
 /// # use render_macros::{component, rsx};
@@ -141,6 +452,9 @@ pub fn rsx(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// When render is re-exported or renamed, the generated code can use another path, like
+/// `#[component(crate = "my_framework::render")]`.
+///
 /// Practically, this is exactly the same as using the [Render](../render/trait.Render.html) trait:
 ///
 /// ```rust
@@ -174,7 +488,11 @@ pub fn rsx(input: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn component(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let path = match crate_path::parse_override.parse(attr) {
+        Ok(path) => path,
+        Err(error) => return TokenStream::from(error.to_compile_error()),
+    };
     let f = parse_macro_input!(item as syn::ItemFn);
-    function_component::create_function_component(f)
+    with_crate_path(path, || function_component::create_function_component(f))
 }
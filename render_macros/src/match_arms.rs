@@ -0,0 +1,133 @@
+use crate::child::Child;
+use crate::children::Children;
+use crate::element::Element;
+use crate::if_else::{either_tokens, parse_braced_children};
+use crate::text::Text;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream, Result};
+
+/// `match value { Pattern if guard => <element />, Pattern => { children } }` among children.
+///
+/// Like branches of `if`, each arm is wrapped in nested `Either`s, so arms can render
+/// different types. An arm renders a single element, a string literal, or children in braces.
+pub struct Match {
+    value: syn::Expr,
+    arms: Vec<MatchArm>,
+}
+
+struct MatchArm {
+    pattern: syn::Pat,
+    guard: Option<syn::Expr>,
+    body: Children,
+}
+
+impl Parse for Match {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<syn::Token![match]>()?;
+        let value = input.call(syn::Expr::parse_without_eager_brace)?;
+
+        let content;
+        syn::braced!(content in input);
+        let mut arms = vec![];
+        while !content.is_empty() {
+            arms.push(content.parse::<MatchArm>()?);
+        }
+
+        Ok(Self { value, arms })
+    }
+}
+
+impl Parse for MatchArm {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let leading_vert = input.parse::<Option<syn::Token![|]>>()?;
+        let cases =
+            syn::punctuated::Punctuated::<syn::Pat, syn::Token![|]>::parse_separated_nonempty(
+                input,
+            )?;
+        let pattern = if cases.len() == 1 && leading_vert.is_none() {
+            cases.into_iter().next().unwrap()
+        } else {
+            syn::Pat::Or(syn::PatOr {
+                attrs: vec![],
+                leading_vert,
+                cases,
+            })
+        };
+
+        let guard = if input.peek(syn::Token![if]) {
+            input.parse::<syn::Token![if]>()?;
+            Some(input.parse::<syn::Expr>()?)
+        } else {
+            None
+        };
+
+        input.parse::<syn::Token![=>]>()?;
+
+        let body = if input.peek(syn::token::Brace) {
+            reject_braced_value(input)?;
+            parse_braced_children(input)?
+        } else if input.peek(syn::LitStr) {
+            Children::new(vec![Child::Text(Text::parse_literal(input)?)])
+        } else {
+            Children::new(vec![Child::Element(input.parse::<Element>()?)])
+        };
+        input.parse::<Option<syn::Token![,]>>()?;
+
+        Ok(Self {
+            pattern,
+            guard,
+            body,
+        })
+    }
+}
+
+/// Braces around an arm hold children, so `{ name }` would render the word "name", and
+/// `{ user.name() }` its source code. Since that is almost never what an expression in braces
+/// means, it fails instead. Literals, blocks and control flow are children as usual.
+fn reject_braced_value(input: ParseStream) -> Result<()> {
+    let fork = input.fork();
+    let content;
+    syn::braced!(content in fork);
+
+    let value = match content.parse::<syn::Expr>() {
+        Ok(value) if content.is_empty() => value,
+        _ => return Ok(()),
+    };
+
+    let message = match &value {
+        syn::Expr::Lit(_)
+        | syn::Expr::Block(_)
+        | syn::Expr::If(_)
+        | syn::Expr::ForLoop(_)
+        | syn::Expr::Match(_) => return Ok(()),
+        syn::Expr::Path(_) | syn::Expr::Field(_) => {
+            let text = value.to_token_stream().to_string().replace(' ', "");
+            format!(
+                "The braces around an arm hold children, so this renders the text `{0}`. \
+                 Use `{{{{{0}}}}}` to render its value, or a string literal for the text",
+                text
+            )
+        }
+        _ => "The braces around an arm hold children, so this renders the expression's code \
+              as text. Wrap it in another pair of braces to render its value"
+            .to_owned(),
+    };
+
+    Err(syn::Error::new_spanned(value, message))
+}
+
+impl ToTokens for Match {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.value;
+        let count = self.arms.len();
+        let arms = self.arms.iter().enumerate().map(|(index, arm)| {
+            let pattern = &arm.pattern;
+            let guard = arm.guard.as_ref().map(|guard| quote!(if #guard));
+            let body = either_tokens(index, count, arm.body.as_renderable_tokens());
+            quote!(#pattern #guard => #body,)
+        });
+
+        quote!(match #value { #(#arms)* }).to_tokens(tokens);
+    }
+}
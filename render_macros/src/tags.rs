@@ -1,37 +1,205 @@
+use crate::crate_path::crate_path;
 use crate::element_attributes::ElementAttributes;
+use proc_macro2::TokenStream;
 use proc_macro_error::abort;
-use quote::quote;
+use quote::ToTokens;
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream, Result};
-use syn::spanned::Spanned;
+use syn::punctuated::Punctuated;
 
-pub struct OpenTag {
-    pub name: syn::Path,
-    pub attributes: ElementAttributes,
-    pub self_closing: bool,
-    pub is_custom_element: bool,
+/// The name of a tag: a path, like `div` or `ui::Button`, or a dash-separated custom element
+/// name, like `my-widget`
+pub enum TagName {
+    Path(syn::Path),
+    Dashed(Punctuated<syn::Ident, syn::Token![-]>),
+}
+
+impl TagName {
+    /// Parses a tag name, or returns `::render::Fragment` when there is none, like in `<>`
+    fn parse_or_fragment(input: ParseStream) -> Result<Self> {
+        if input.peek(syn::Token![>]) {
+            let render = crate_path();
+            return Ok(Self::Path(syn::parse_quote!(#render::Fragment)));
+        }
+
+        let path = parse_path(input)?;
+
+        match path.get_ident() {
+            Some(ident) if input.peek(syn::Token![-]) => {
+                let mut segments = Punctuated::new();
+                segments.push_value(ident.clone());
+                while input.peek(syn::Token![-]) {
+                    segments.push_punct(input.parse()?);
+                    segments.push_value(input.call(syn::Ident::parse_any)?);
+                }
+                Ok(Self::Dashed(segments))
+            }
+            _ => Ok(Self::Path(path)),
+        }
+    }
+
+    /// Whether the tag is a component. Components start with an uppercase, or are paths;
+    /// dashed names are always custom HTML elements.
+    pub fn is_custom_element(&self) -> bool {
+        match self {
+            Self::Dashed(_) => false,
+            Self::Path(path) => match path.get_ident() {
+                None => true,
+                Some(ident) => {
+                    let name = ident.to_string();
+                    let first_letter = name.get(0..1).unwrap();
+                    first_letter.to_uppercase() == first_letter
+                }
+            },
+        }
+    }
+
+    /// The tag name as written in HTML, or the path as written in Rust, like `List::<u8>`
+    pub fn html_name(&self) -> String {
+        match self {
+            Self::Path(path) => path_name(path),
+            Self::Dashed(segments) => segments
+                .iter()
+                .map(|segment| segment.unraw().to_string())
+                .collect::<Vec<_>>()
+                .join("-"),
+        }
+    }
+}
+
+/// A path as it is written in Rust, like `ui::List::<&'a str>`
+fn path_name(path: &syn::Path) -> String {
+    let segments: Vec<_> = path
+        .segments
+        .iter()
+        .map(|segment| {
+            let mut name = segment.ident.unraw().to_string();
+            match &segment.arguments {
+                syn::PathArguments::None => {}
+                syn::PathArguments::AngleBracketed(generics) => {
+                    if generics.colon2_token.is_some() {
+                        name.push_str("::");
+                    }
+                    let arguments: Vec<_> = generics.args.iter().map(argument_name).collect();
+                    name.push_str(&format!("<{}>", arguments.join(", ")));
+                }
+                arguments => name.push_str(&arguments.to_token_stream().to_string()),
+            }
+            name
+        })
+        .collect();
+
+    let leading_colon = if path.leading_colon.is_some() {
+        "::"
+    } else {
+        ""
+    };
+    format!("{}{}", leading_colon, segments.join("::"))
+}
+
+fn argument_name(argument: &syn::GenericArgument) -> String {
+    match argument {
+        syn::GenericArgument::Type(ty) => type_name(ty),
+        syn::GenericArgument::Lifetime(lifetime) => lifetime.to_string(),
+        syn::GenericArgument::Binding(binding) => {
+            format!("{} = {}", binding.ident, type_name(&binding.ty))
+        }
+        argument => argument.to_token_stream().to_string(),
+    }
+}
+
+fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(ty) if ty.qself.is_none() => path_name(&ty.path),
+        syn::Type::Reference(reference) => {
+            let lifetime = reference
+                .lifetime
+                .as_ref()
+                .map(|lifetime| format!("{} ", lifetime));
+            let mutability = reference.mutability.map(|_| "mut ");
+            format!(
+                "&{}{}{}",
+                lifetime.unwrap_or_default(),
+                mutability.unwrap_or_default(),
+                type_name(&reference.elem)
+            )
+        }
+        syn::Type::Tuple(tuple) => {
+            let elements: Vec<_> = tuple.elems.iter().map(type_name).collect();
+            match elements.as_slice() {
+                [element] => format!("({},)", element),
+                elements => format!("({})", elements.join(", ")),
+            }
+        }
+        syn::Type::Slice(slice) => format!("[{}]", type_name(&slice.elem)),
+        ty => ty.to_token_stream().to_string(),
+    }
+}
+
+/// Parses a path like in an expression, so generic arguments need a turbofish, like in
+/// `List::<User>`. Segments can be keywords, like in `<use>` or `<Self>`.
+fn parse_path(input: ParseStream) -> Result<syn::Path> {
+    let leading_colon = input.parse::<Option<syn::Token![::]>>()?;
+    let mut segments = Punctuated::new();
+
+    loop {
+        let ident = input.call(syn::Ident::parse_any)?;
+        let arguments = if input.peek(syn::Token![::]) && input.peek3(syn::Token![<]) {
+            syn::PathArguments::AngleBracketed(input.parse()?)
+        } else {
+            syn::PathArguments::None
+        };
+        segments.push_value(syn::PathSegment { ident, arguments });
+
+        if !input.peek(syn::Token![::]) {
+            break;
+        }
+        segments.push_punct(input.parse()?);
+    }
+
+    Ok(syn::Path {
+        leading_colon,
+        segments,
+    })
 }
 
-fn name_or_fragment(maybe_name: Result<syn::Path>) -> syn::Path {
-    maybe_name.unwrap_or_else(|_| syn::parse_str::<syn::Path>("::render::Fragment").unwrap())
+/// Whether a closing tag's path closes an opening tag's path. Generic arguments can be left
+/// out of the closing tag, like in `<List::<User>>...</List>`.
+fn closes(closing: &syn::Path, open: &syn::Path) -> bool {
+    closing.leading_colon.is_some() == open.leading_colon.is_some()
+        && closing.segments.len() == open.segments.len()
+        && closing
+            .segments
+            .iter()
+            .zip(open.segments.iter())
+            .all(|(closing, open)| {
+                closing.ident == open.ident
+                    && (closing.arguments.is_empty()
+                        || closing.arguments.to_token_stream().to_string()
+                            == open.arguments.to_token_stream().to_string())
+            })
 }
 
-fn is_custom_element_name(path: &syn::Path) -> bool {
-    match path.get_ident() {
-        None => true,
-        Some(ident) => {
-            let name = ident.to_string();
-            let first_letter = name.get(0..1).unwrap();
-            first_letter.to_uppercase() == first_letter
+impl ToTokens for TagName {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Path(path) => path.to_tokens(tokens),
+            Self::Dashed(segments) => segments.to_tokens(tokens),
         }
     }
 }
 
+pub struct OpenTag {
+    pub name: TagName,
+    pub attributes: ElementAttributes,
+    pub self_closing: bool,
+}
+
 impl Parse for OpenTag {
     fn parse(input: ParseStream) -> Result<Self> {
         input.parse::<syn::Token![<]>()?;
-        let maybe_name = syn::Path::parse_mod_style(input);
-        let name = name_or_fragment(maybe_name);
-        let is_custom_element = is_custom_element_name(&name);
+        let name = TagName::parse_or_fragment(input)?;
+        let is_custom_element = name.is_custom_element();
         let attributes = ElementAttributes::parse(input, is_custom_element)?;
         let self_closing = input.parse::<syn::Token![/]>().is_ok();
         input.parse::<syn::Token![>]>()?;
@@ -40,26 +208,29 @@ impl Parse for OpenTag {
             name,
             attributes,
             self_closing,
-            is_custom_element,
         })
     }
 }
 
 pub struct ClosingTag {
-    name: syn::Path,
+    name: TagName,
 }
 
 impl ClosingTag {
     pub fn validate(&self, open_tag: &OpenTag) {
-        let open_tag_path = &open_tag.name;
-        let open_tag_path_str = quote!(#open_tag_path).to_string();
-        let self_path = &self.name;
-        let self_path_str = quote!(#self_path).to_string();
-        if self_path_str != open_tag_path_str {
+        let is_matching = match (&self.name, &open_tag.name) {
+            (TagName::Path(closing), TagName::Path(open)) => closes(closing, open),
+            (TagName::Dashed(_), TagName::Dashed(_)) => {
+                self.name.html_name() == open_tag.name.html_name()
+            }
+            _ => false,
+        };
+
+        if !is_matching {
             abort!(
-                self.name.span(),
+                self.name,
                 "Expected closing tag for: <{}>",
-                &open_tag_path_str
+                open_tag.name.html_name()
             );
         }
     }
@@ -69,10 +240,8 @@ impl Parse for ClosingTag {
     fn parse(input: ParseStream) -> Result<Self> {
         input.parse::<syn::Token![<]>()?;
         input.parse::<syn::Token![/]>()?;
-        let maybe_name = input.parse::<syn::Path>();
+        let name = TagName::parse_or_fragment(input)?;
         input.parse::<syn::Token![>]>()?;
-        Ok(Self {
-            name: name_or_fragment(maybe_name),
-        })
+        Ok(Self { name })
     }
 }
@@ -0,0 +1,273 @@
+use crate::child::Child;
+use crate::children::is_closing_tag;
+use crate::entities::ENTITIES;
+use proc_macro2::{Delimiter, Spacing, Span, TokenTree};
+use quote::{quote_spanned, ToTokens};
+use syn::parse::{ParseStream, Result};
+
+/// A run of text children, made of string literals and bare words.
+///
+/// Token streams don't keep whitespace, so it is derived from the tokens themselves: words
+/// and literals are separated by a single space, except after punctuation glued to the next
+/// token, like in `?!`, before punctuation that closes a phrase, like `,` or `)`, after `(`,
+/// `[` and `$`, around `-`, `/` and `@`, like in `well-known`, around a `.` or `:` that
+/// continues a word, like in `example.com` or `10:30`, and next to whitespace characters,
+/// like `&nbsp;`. Text is separated from an element or block next to it the same way, but
+/// doesn't start or end with a space inside an element, and string literals are written
+/// without a space between them and the element or block next to them.
+///
+/// Character references are decoded when the text is parsed, so they are escaped again only
+/// when needed.
+pub struct Text {
+    value: String,
+    span: Span,
+}
+
+impl Text {
+    pub fn is_start(input: ParseStream) -> bool {
+        !input.is_empty() && !input.peek(syn::Token![<]) && !input.peek(syn::token::Brace)
+    }
+
+    /// Parses a run of text, until the next element, block or control flow. `after_sibling`
+    /// is whether it follows another child of the same element.
+    pub fn parse_after(input: ParseStream, after_sibling: bool) -> Result<Self> {
+        let span = input.span();
+        let mut builder = TextBuilder::default();
+
+        let mut tokens = vec![];
+        while Self::is_start(input) && !Child::starts_control_flow(input) {
+            tokens.push(input.parse::<TokenTree>()?);
+        }
+        builder.push_tokens(&tokens)?;
+
+        let before_sibling = !input.is_empty() && !is_closing_tag(input);
+
+        Ok(Self {
+            value: builder.finish(after_sibling, before_sibling),
+            span,
+        })
+    }
+}
+
+impl Text {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Parses a single string literal, rendered as is
+    pub fn parse_literal(input: ParseStream) -> Result<Self> {
+        let literal = input.parse::<syn::LitStr>()?;
+        Ok(Self {
+            value: literal.value(),
+            span: literal.span(),
+        })
+    }
+}
+
+impl ToTokens for Text {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let value = &self.value;
+        quote_spanned!(self.span=> #value).to_tokens(tokens);
+    }
+}
+
+struct Piece {
+    text: String,
+    /// Whether the next piece is glued to this one, like the `'` and `t` in `don't`
+    joint: bool,
+    /// Whether this is a string literal, whose whitespace is exact
+    literal: bool,
+}
+
+impl Piece {
+    /// Whether there is no space before this piece, like a `,` or `)` closing a phrase
+    fn attaches_to_previous(&self) -> bool {
+        self.text
+            .starts_with(|c: char| c.is_whitespace() || ",.;:!?)]%'".contains(c))
+            || !self.literal && GLUED.contains(&self.text.as_str())
+    }
+
+    /// Whether there is no space after this piece, like an opening `(`
+    fn attaches_to_next(&self) -> bool {
+        self.joint
+            || self
+                .text
+                .ends_with(|c: char| c.is_whitespace() || "([".contains(c))
+            || !self.literal && (GLUED.contains(&self.text.as_str()) || self.text == "$")
+    }
+
+    /// Whether `next` continues a word across this `.` or `:`, like in `example.com` or `10:30`
+    fn continues_into(&self, next: &Piece) -> bool {
+        let first = match next.text.chars().next() {
+            Some(first) if !self.literal && !next.literal => first,
+            _ => return false,
+        };
+
+        match self.text.as_str() {
+            "." => first.is_ascii_lowercase() || first.is_ascii_digit(),
+            ":" => first.is_ascii_digit(),
+            _ => false,
+        }
+    }
+}
+
+/// Punctuation glued to the words on both sides, like in `well-known`, `and/or` or
+/// `user@example.com`
+const GLUED: &[&str] = &["-", "/", "@"];
+
+#[derive(Default)]
+struct TextBuilder {
+    pieces: Vec<Piece>,
+}
+
+impl TextBuilder {
+    fn push_tokens(&mut self, tokens: &[TokenTree]) -> Result<()> {
+        let mut rest = tokens;
+        while let Some(token) = rest.first() {
+            if let Some((text, length)) = entity(rest)? {
+                let joint = matches!(&rest[length - 1], TokenTree::Punct(semi) if semi.spacing() == Spacing::Joint);
+                self.push(&text, joint, false);
+                rest = &rest[length..];
+            } else {
+                self.push_token(token)?;
+                rest = &rest[1..];
+            }
+        }
+        Ok(())
+    }
+
+    fn push_token(&mut self, token: &TokenTree) -> Result<()> {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                self.push(open, false, false);
+                self.push_tokens(&group.stream().into_iter().collect::<Vec<_>>())?;
+                self.push(close, false, false);
+            }
+            TokenTree::Literal(literal) => {
+                let token = TokenTree::Literal(literal.clone());
+                match syn::parse2::<syn::LitStr>(token.into()) {
+                    Ok(string) => self.push(&string.value(), false, true),
+                    Err(_) => self.push(&literal.to_string(), false, false),
+                }
+            }
+            TokenTree::Ident(ident) => {
+                let ident = ident.to_string();
+                self.push(ident.trim_start_matches("r#"), false, false);
+            }
+            TokenTree::Punct(punct) => {
+                let joint = punct.spacing() == Spacing::Joint;
+                self.push(&punct.as_char().to_string(), joint, false);
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, text: &str, joint: bool, literal: bool) {
+        if !text.is_empty() {
+            self.pieces.push(Piece {
+                text: text.to_owned(),
+                joint,
+                literal,
+            });
+        }
+    }
+
+    fn finish(self, after_sibling: bool, before_sibling: bool) -> String {
+        let mut value = String::new();
+
+        if let (true, Some(first)) = (after_sibling, self.pieces.first()) {
+            if !first.literal && !first.attaches_to_previous() {
+                value.push(' ');
+            }
+        }
+
+        let mut previous: Option<&Piece> = None;
+        for piece in self.pieces.iter() {
+            if let Some(previous) = previous {
+                if !previous.attaches_to_next()
+                    && !piece.attaches_to_previous()
+                    && !previous.continues_into(piece)
+                {
+                    value.push(' ');
+                }
+            }
+            value.push_str(&piece.text);
+            previous = Some(piece);
+        }
+
+        if let (true, Some(last)) = (before_sibling, self.pieces.last()) {
+            if !last.literal && !last.attaches_to_next() {
+                value.push(' ');
+            }
+        }
+
+        value
+    }
+}
+
+/// Decodes a character reference at the start of the tokens, like `&nbsp;`, `&#169;` or
+/// `&#x2014;`, and returns its text and how many tokens it spans
+fn entity(tokens: &[TokenTree]) -> Result<Option<(String, usize)>> {
+    let (reference, length) = match tokens {
+        [TokenTree::Punct(amp), TokenTree::Ident(name), TokenTree::Punct(semi), ..]
+            if amp.as_char() == '&' && semi.as_char() == ';' =>
+        {
+            (name.to_string(), 3)
+        }
+        // `&#` is only joint when the two are glued
+        [TokenTree::Punct(amp), TokenTree::Punct(hash), number @ (TokenTree::Ident(_) | TokenTree::Literal(_)), TokenTree::Punct(semi), ..]
+            if amp.as_char() == '&'
+                && amp.spacing() == Spacing::Joint
+                && hash.as_char() == '#'
+                && semi.as_char() == ';' =>
+        {
+            (format!("#{}", number), 4)
+        }
+        _ => return Ok(None),
+    };
+
+    let text = match reference.strip_prefix('#') {
+        Some(number) => {
+            let code = match number.strip_prefix(|c| c == 'x' || c == 'X') {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => number.parse(),
+            };
+            code.ok()
+                .filter(|code| *code != 0)
+                .and_then(char::from_u32)
+                .map(String::from)
+        }
+        // Tokens don't tell whether `& name ;` is glued, so text like `Tom & Jerry;` fails
+        // too, and has to write its `&` as a string literal
+        None => {
+            let entity = ENTITIES.binary_search_by(|(name, _)| (*name).cmp(reference.as_str()));
+            match entity {
+                Ok(index) => Some(ENTITIES[index].1.to_owned()),
+                Err(_) => {
+                    return Err(syn::Error::new(
+                        tokens[1].span(),
+                        format!("Unknown character reference `&{};`", reference),
+                    ))
+                }
+            }
+        }
+    };
+
+    match text {
+        Some(text) => Ok(Some((text, length))),
+        None => Err(syn::Error::new(
+            tokens[length - 2].span(),
+            format!("Invalid character reference `&{};`", reference),
+        )),
+    }
+}
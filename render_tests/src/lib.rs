@@ -171,6 +171,587 @@ fn vec() {
     )
 }
 
+#[test]
+fn class_list() {
+    use pretty_assertions::assert_eq;
+    use render::{classes, component, html, rsx};
+
+    #[component]
+    fn Button<'a>(kind: &'a str, disabled: bool) {
+        rsx! {
+            <button class={classes!("btn", format!("btn-{}", kind), ("btn-disabled", disabled))} />
+        }
+    }
+
+    assert_eq!(
+        html! { <Button kind={"primary"} disabled={true} /> },
+        r#"<button class="btn btn-primary btn-disabled"/>"#
+    );
+    assert_eq!(
+        html! { <Button kind={"link"} disabled={false} /> },
+        r#"<button class="btn btn-link"/>"#
+    );
+}
+
+#[test]
+fn context_aware_escaping() {
+    use pretty_assertions::assert_eq;
+    use render::{html, TrustedScript};
+
+    let user_name = "</script><script>alert(1)</script>";
+    let profile_url = "/users/Gal Schlezinger";
+
+    assert_eq!(
+        html! {
+            <>
+                <script>{format!("window.user = \"{}\";", user_name)}</script>
+                <a href={profile_url}>{user_name}</a>
+                <button onclick={TrustedScript::new("track('profile')")} />
+            </>
+        },
+        concat!(
+            r#"<script>window.user = "<\/script><script>alert(1)<\/script>";</script>"#,
+            r#"<a href="/users/Gal%20Schlezinger">"#,
+            "&lt;/script&gt;&lt;script&gt;alert(1)&lt;/script&gt;</a>",
+            r#"<button onclick="track(&apos;profile&apos;)"/>"#,
+        )
+    );
+}
+
+#[test]
+fn text_children() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    let name = "Gal";
+
+    assert_eq!(
+        html! {
+            <div>
+                Welcome back,
+                <b>{name}</b>. Don't miss our "<new>" deals
+                (up to 50% off)!
+                <a href={"/deals"}>See all</a>
+            </div>
+        },
+        concat!(
+            "<div>Welcome back, <b>Gal</b>. Don&apos;t miss our &lt;new&gt; deals ",
+            r#"(up to 50% off)! <a href="/deals">See all</a></div>"#
+        )
+    );
+    assert_eq!(html! { <p>{name} says hi</p> }, "<p>Gal says hi</p>");
+    assert_eq!(html! { <p>"  exact  "</p> }, "<p>  exact  </p>");
+    assert_eq!(html! { <p>"a"<b>b</b>"c"</p> }, "<p>a<b>b</b>c</p>");
+    assert_eq!(
+        html! { <p>A well-known e-mail at 10:30. Write to user@example.com and/or pay $5</p> },
+        "<p>A well-known e-mail at 10:30. Write to user@example.com and/or pay $5</p>"
+    );
+    assert_eq!(html! { <p>for a while</p> }, "<p>for a while</p>");
+    assert_eq!(html! { <p>let it be</p> }, "<p>let it be</p>");
+    assert_eq!(html! { <p>match point</p> }, "<p>match point</p>");
+    assert_eq!(html! { <p>if only</p> }, "<p>if only</p>");
+}
+
+#[test]
+fn attribute_values() {
+    use pretty_assertions::assert_eq;
+    use render::{component, html, rsx};
+
+    #[component]
+    fn Badge<'a>(label: &'a str, count: usize, class: String) {
+        rsx! { <span class>{format!("{} ({})", label, count)}</span> }
+    }
+
+    struct User {
+        name: String,
+        id: u32,
+    }
+
+    let user = User {
+        name: "Gal Schlezinger".into(),
+        id: 7,
+    };
+
+    assert_eq!(
+        html! {
+            <div>
+                <a href="/users/{user.id}/{user.name}">{user.name.as_str()}</a>
+                <span class="id-{user.id + 1} {{literal}}" />
+                <img alt=user.name.as_str() />
+                <Badge label="Posts" count=3 class="badge-{user.id}" />
+            </div>
+        },
+        concat!(
+            r#"<div><a href="/users/7/Gal%20Schlezinger">Gal Schlezinger</a>"#,
+            r#"<span class="id-8 {literal}"/><img alt="Gal Schlezinger"/>"#,
+            r#"<span class="badge-7">Posts (3)</span></div>"#
+        )
+    );
+
+    // The scheme is checked even when it is written in pieces
+    let link = |scheme: &str| html! { <a href="{scheme}:/items/{user.id}">Item</a> };
+    assert_eq!(link("https"), r#"<a href="https:/items/7">Item</a>"#);
+    assert_eq!(link("java"), r#"<a href="about:invalid">Item</a>"#);
+}
+
+#[test]
+fn if_else() {
+    use pretty_assertions::assert_eq;
+    use render::{html, rsx, Render};
+
+    let greeting = |user: Option<&str>, admin: bool| {
+        rsx! {
+            <header>
+                if let Some(name) = user {
+                    Hello, <b>{name}</b>
+                    if admin { " (admin)" }
+                } else {
+                    <a href="/login">Log in</a>
+                }
+            </header>
+        }
+        .render()
+    };
+
+    assert_eq!(
+        greeting(Some("Gal"), true),
+        "<header>Hello, <b>Gal</b> (admin)</header>"
+    );
+    assert_eq!(
+        greeting(Some("Gal"), false),
+        "<header>Hello, <b>Gal</b></header>"
+    );
+    assert_eq!(
+        greeting(None, true),
+        r#"<header><a href="/login">Log in</a></header>"#
+    );
+    assert_eq!(html! { <p>Go if you can</p> }, "<p>Go if you can</p>");
+
+    let total = 3;
+    assert_eq!(
+        html! { <p>Total: if total > 2 { "many" } else { {total} }</p> },
+        "<p>Total: many</p>"
+    );
+    assert_eq!(
+        html! { <p>Items: for i in 0..total { {i} }. Done let x = 1; {x}</p> },
+        "<p>Items: 012. Done 1</p>"
+    );
+}
+
+#[test]
+fn for_loop() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    struct Section {
+        title: &'static str,
+        items: Vec<&'static str>,
+    }
+
+    let sections = vec![
+        Section {
+            title: "Fruits",
+            items: vec!["apple", "banana"],
+        },
+        Section {
+            title: "Empty",
+            items: vec![],
+        },
+    ];
+
+    assert_eq!(
+        html! {
+            <div>
+                for Section { title, items } in sections {
+                    <h2>{title}</h2>
+                    if items.is_empty() {
+                        <p>Nothing here</p>
+                    } else {
+                        <ol>
+                            for (index, item) in items.into_iter().enumerate() {
+                                <li value=index.to_string()>{item}</li>
+                            }
+                        </ol>
+                    }
+                }
+            </div>
+        },
+        concat!(
+            "<div><h2>Fruits</h2>",
+            r#"<ol><li value="0">apple</li><li value="1">banana</li></ol>"#,
+            "<h2>Empty</h2><p>Nothing here</p></div>"
+        )
+    );
+}
+
+#[test]
+fn match_arms() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    let describe = |value: Result<u32, &str>| {
+        html! {
+            <p>
+                match value {
+                    Ok(0) | Err("") => "Nothing",
+                    Ok(n) if n % 2 == 0 => <b>{n} is even</b>,
+                    Ok(n) => {
+                        <i>{n}</i> is odd
+                    }
+                    Err(message) => <span class="error">{message}</span>
+                }
+            </p>
+        }
+    };
+
+    assert_eq!(describe(Ok(0)), "<p>Nothing</p>");
+    assert_eq!(describe(Err("")), "<p>Nothing</p>");
+    assert_eq!(describe(Ok(4)), "<p><b>4 is even</b></p>");
+    assert_eq!(describe(Ok(3)), "<p><i>3</i> is odd</p>");
+    assert_eq!(
+        describe(Err("oops")),
+        r#"<p><span class="error">oops</span></p>"#
+    );
+
+    let name = "Gal";
+    assert_eq!(
+        html! { <p>match 1 { 1 => { {name} } _ => "name" }</p> },
+        "<p>Gal</p>"
+    );
+}
+
+#[test]
+fn let_bindings() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    let items = vec![("Tea", 350), ("Cake", 1275)];
+    let writer = "the menu";
+
+    let rendered = html! {
+        <ul title={writer}>
+            for (name, cents) in items {
+                let price = format!("${}.{:02}", cents / 100, cents % 100);
+                <li aria-label={format!("{} for {}", name, price)}>
+                    {name}: <b>{price.as_str()}</b>
+                </li>
+            }
+            let total: usize = 2;
+            <li>{total} items in {writer}</li>
+        </ul>
+    };
+
+    assert_eq!(
+        rendered,
+        concat!(
+            r#"<ul title="the menu">"#,
+            r#"<li aria-label="Tea for $3.50">Tea: <b>$3.50</b></li>"#,
+            r#"<li aria-label="Cake for $12.75">Cake: <b>$12.75</b></li>"#,
+            "<li>2 items in the menu</li></ul>"
+        )
+    );
+
+    // Variables from outside are borrowed, so they can still be used afterwards
+    let title = String::from("Menu");
+    let rendered = html! { <div> let count = 1; <p>{title.as_str()}{count}</p></div> };
+    assert_eq!(rendered, "<div><p>Menu1</p></div>");
+    assert_eq!(title, "Menu");
+}
+
+#[test]
+fn comments_and_doctype() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    let version = 3;
+    let sneaky = "--><script>alert(1)</script><!--";
+
+    assert_eq!(
+        html! {
+            <>
+                <!doctype HTML>
+                <body>
+                    <!-- "[if IE]><p>Please upgrade</p><![endif]" -->
+                    Version <!-- {version} --> here
+                    <!-- {sneaky} -->
+                </body>
+            </>
+        },
+        concat!(
+            "<!DOCTYPE html><body><!--[if IE]><p>Please upgrade</p><![endif]--> ",
+            "Version <!--3--> here <!--- -><script>alert(1)</script><!- - -->",
+            "</body>"
+        )
+    );
+}
+
+#[test]
+fn character_references() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    assert_eq!(
+        html! {
+            <footer>
+                &copy;&nbsp;2024 Tom {"&"} Jerry; "AT&T" &mdash; 1 &lt; 2 (&#169; &#X2014;)
+            </footer>
+        },
+        "<footer>©\u{a0}2024 Tom &amp; Jerry; AT&amp;T — 1 &lt; 2 (© —)</footer>"
+    );
+}
+
+#[test]
+fn web_components() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    assert_eq!(
+        html! {
+            <turbo-frame id={"messages"}>
+                <my-fancy-widget />
+                <x-for r#type={"list"}>Items</x-for>
+            </turbo-frame>
+        },
+        concat!(
+            r#"<turbo-frame id="messages"><my-fancy-widget/>"#,
+            r#"<x-for type="list">Items</x-for></turbo-frame>"#
+        )
+    );
+}
+
+#[test]
+fn attribute_names() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+
+    let lang = "he";
+
+    assert_eq!(
+        html! { <use xlink:href="#icon" /> },
+        r##"<use xlink:href="#icon"/>"##
+    );
+    assert_eq!(html! { <p xml:lang=lang /> }, r#"<p xml:lang="he"/>"#);
+    assert_eq!(
+        html! { <div x-on:click.outside="open = false" /> },
+        r#"<div x-on:click.outside="open = false"/>"#
+    );
+    assert_eq!(
+        html! { <div :class="{{ active }}" /> },
+        r#"<div :class="{ active }"/>"#
+    );
+    assert_eq!(
+        html! { <form hx-on::after-request="this.reset()" /> },
+        r#"<form hx-on::after-request="this.reset()"/>"#
+    );
+    assert_eq!(html! { <td data-level-2=2 /> }, r#"<td data-level-2="2"/>"#);
+}
+
+#[test]
+fn spread_attributes() {
+    use pretty_assertions::assert_eq;
+    use render::html;
+    use std::collections::BTreeMap;
+
+    let mut config = BTreeMap::new();
+    config.insert(String::from("data-theme"), String::from("dark"));
+    let id = "main";
+
+    assert_eq!(
+        html! { <main {..&config} /> },
+        r#"<main data-theme="dark"/>"#
+    );
+    assert_eq!(
+        html! { <main id {..vec![("ID", "ignored")]} /> },
+        r#"<main ID="ignored"/>"#
+    );
+    assert_eq!(
+        html! { <main {..vec![("ID", "ignored")]} id /> },
+        r#"<main id="main"/>"#
+    );
+    assert_eq!(
+        html! { <main {..Some(("x\" onload=\"alert(1)", "a"))} /> },
+        "<main/>"
+    );
+    assert_eq!(
+        html! { <a {..Some(("href", "javascript:alert(1)"))} /> },
+        r#"<a href="about:invalid"/>"#
+    );
+}
+
+#[test]
+fn spread_props() {
+    use pretty_assertions::assert_eq;
+    use render::{component, html, rsx};
+
+    #[component]
+    fn Button<'a, Children: render::Render>(
+        label: &'a str,
+        kind: &'a str,
+        disabled: bool,
+        children: Children,
+    ) {
+        rsx! {
+            <button class={format!("btn btn-{}", kind)}>
+                {label}: {children}
+                if disabled { " (disabled)" }
+            </button>
+        }
+    }
+
+    #[component]
+    fn DangerButton<'a>(button: Button<'a, &'a str>) {
+        rsx! { <Button {..button} kind={"danger"} /> }
+    }
+
+    let delete = Button {
+        label: "Delete",
+        kind: "primary",
+        disabled: false,
+        children: "Delete everything",
+    };
+
+    assert_eq!(
+        html! { <DangerButton button={delete} /> },
+        r#"<button class="btn btn-danger">Delete: Delete everything</button>"#
+    );
+}
+
+#[test]
+fn multiple_roots() {
+    use pretty_assertions::assert_eq;
+    use render::{component, html, rsx};
+
+    #[component]
+    fn Field<'a>(label: &'a str) {
+        rsx! {
+            <label for=label>{label}</label>
+            <input id=label />
+        }
+    }
+
+    let name = "Gal";
+
+    assert_eq!(
+        html! { <Field label={"email"} /> },
+        r#"<label for="email">email</label><input id="email"/>"#
+    );
+    assert_eq!(html! { Hello, <b>{name}</b>! }, "Hello, <b>Gal</b>!");
+    assert_eq!(html! { {name} }, "Gal");
+    assert_eq!(html! {}, "");
+}
+
+#[test]
+fn generic_components() {
+    use pretty_assertions::assert_eq;
+    use render::{component, html, rsx, Render};
+
+    #[component]
+    fn Labeled<T: Render>(label: &'static str, value: T) {
+        rsx! { <span title=label>{value}</span> }
+    }
+
+    #[derive(Debug)]
+    struct Pair<T> {
+        first: T,
+        second: T,
+    }
+
+    impl<T: Render> Render for Pair<T> {
+        fn render_into<W: std::fmt::Write>(self, writer: &mut W) -> std::fmt::Result {
+            let Pair { first, second } = self;
+            Render::render_into(rsx! { <b>{first}</b><i>{second}</i> }, writer)
+        }
+    }
+
+    impl Pair<&'static str> {
+        fn swapped(self) -> impl Render {
+            rsx! { <Self first={self.second} second={self.first} /> }
+        }
+    }
+
+    assert_eq!(
+        html! { <Labeled::<u8> label={"Age"} value={30} /> },
+        r#"<span title="Age">30</span>"#
+    );
+    assert_eq!(
+        html! { <Pair::<&str> first={"a"} second={"b"}></Pair> },
+        "<b>a</b><i>b</i>"
+    );
+    assert_eq!(
+        Pair {
+            first: "a",
+            second: "b"
+        }
+        .swapped()
+        .render(),
+        "<b>b</b><i>a</i>"
+    );
+}
+
+#[test]
+fn crate_path_override() {
+    use pretty_assertions::assert_eq;
+    use render::{component, html, rsx};
+
+    mod framework {
+        pub use render as reexported;
+    }
+
+    #[component(crate = "framework::reexported")]
+    fn Item<'a>(label: &'a str) {
+        rsx! { crate = framework::reexported; <li class="item">{format!("{}!", label)}</li> }
+    }
+
+    let labels = ["a", "b"];
+    let attributes = Some(("id", "list"));
+
+    assert_eq!(
+        html! {
+            crate = framework::reexported;
+            <!DOCTYPE html>
+            <ul {..attributes}>
+                for label in labels.iter() {
+                    let upper = label.to_uppercase();
+                    if label == &"a" { <Item label={&upper} /> } else { <>{upper}</> }
+                }
+            </ul>
+        },
+        r#"<!DOCTYPE html><ul id="list"><li class="item">A!</li>B</ul>"#
+    );
+}
+
+#[test]
+fn json_script() {
+    use pretty_assertions::assert_eq;
+    use render::{html, JsonScript};
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Article<'a> {
+        #[serde(rename = "@type")]
+        kind: &'a str,
+        headline: &'a str,
+    }
+
+    let article = Article {
+        kind: "Article",
+        headline: "Tom & Jerry </script>",
+    };
+
+    assert_eq!(
+        html! {
+            <head>
+                {JsonScript::new(&article).json_ld().id("article")}
+            </head>
+        },
+        concat!(
+            r#"<head><script type="application/ld+json" id="article">"#,
+            r#"{"@type":"Article","headline":"Tom \u0026 Jerry \u003c/script\u003e"}"#,
+            "</script></head>"
+        )
+    );
+}
+
 mod kaki {
     // A simple HTML 5 doctype declaration
     use render::html::HTML5Doctype;
@@ -247,7 +828,6 @@ mod kaki {
 /// ## Other
 ///
 /// Module for testing component visibility when imported from other modules.
-
 mod other {
     use render::html::HTML5Doctype;
     use render::{component, rsx, Render};
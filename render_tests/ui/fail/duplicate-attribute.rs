@@ -0,0 +1,5 @@
+use render::html;
+
+fn main() {
+    html! { <use xlink:href={"#a"} XLINK:HREF={"#b"} /> };
+}
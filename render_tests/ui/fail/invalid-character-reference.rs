@@ -0,0 +1,7 @@
+use render::html;
+
+fn main() {
+    html! {
+        <p>Fish &#xD800; chips</p>
+    };
+}
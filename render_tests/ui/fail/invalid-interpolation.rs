@@ -0,0 +1,6 @@
+use render::html;
+
+fn main() {
+    let kind = "primary";
+    html! { <a class="btn btn-{kind" /> };
+}
@@ -0,0 +1,29 @@
+use render::html;
+
+struct User;
+
+impl User {
+    fn name(&self) -> &str {
+        "Gal"
+    }
+}
+
+fn main() {
+    let user = Some(User);
+    html! {
+        <p>
+            match &user {
+                Some(user) => { user.name() }
+                None => "Nobody",
+            }
+        </p>
+    };
+    html! {
+        <p>
+            match user {
+                Some(_) => { format!("{}", 1) }
+                None => "Nobody",
+            }
+        </p>
+    };
+}
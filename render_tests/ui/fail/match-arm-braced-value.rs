@@ -0,0 +1,13 @@
+use render::html;
+
+fn main() {
+    let name = "Gal";
+    html! {
+        <p>
+            match 1 {
+                1 => { name }
+                _ => "Nobody",
+            }
+        </p>
+    };
+}
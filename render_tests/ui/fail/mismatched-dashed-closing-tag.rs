@@ -0,0 +1,7 @@
+use render::html;
+
+fn main() {
+    html! {
+        <my-widget>Hello</my-widget-x>
+    };
+}
@@ -0,0 +1,15 @@
+use render::{component, html, rsx, Render};
+
+#[component]
+fn List<T: Render>(items: Vec<T>) {
+    rsx! { <ul>{items}</ul> }
+}
+
+fn main() {
+    html! {
+        <List::<u8> items={vec![1]}></List::<u16>>
+    };
+    html! {
+        <List::<(&'static str, Vec<u8>)> items={vec![]}></List::<u8>>
+    };
+}
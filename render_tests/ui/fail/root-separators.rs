@@ -0,0 +1,8 @@
+use render::html;
+
+fn main() {
+    html! {
+        <li>{"One"}</li>,
+        <li>{"Two"}</li>
+    };
+}
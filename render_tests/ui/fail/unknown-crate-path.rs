@@ -0,0 +1,8 @@
+use render::html;
+
+fn main() {
+    html! {
+        crate = missing::render;
+        <div />
+    };
+}
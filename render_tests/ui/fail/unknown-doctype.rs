@@ -0,0 +1,10 @@
+use render::html;
+
+fn main() {
+    html! {
+        <>
+            <!DOCTYPE svg>
+            <svg />
+        </>
+    };
+}
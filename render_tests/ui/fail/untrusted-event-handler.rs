@@ -0,0 +1,10 @@
+use render::html;
+
+fn main() {
+    let handler = "alert(document.cookie)";
+    html! { <button onclick={handler} /> };
+    html! { <button ONCLICK={handler} /> };
+    html! { <button @click={handler} /> };
+    html! { <button x-on:click={handler} /> };
+    html! { <button hx-on::after-request={handler} /> };
+}